@@ -1,8 +1,39 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-#[derive(PartialEq)]
+use aptos_api_types::mime_types::BCS;
+use std::convert::Infallible;
+use warp::{
+    http::{header::ACCEPT, HeaderMap},
+    Filter,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AcceptType {
     Json,
     Bcs,
 }
+
+impl AcceptType {
+    // BCS if any of the media types in the Accept header is BCS, JSON otherwise.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let accepts_bcs = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|media_type| media_type.split(';').next())
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(BCS));
+        if accepts_bcs {
+            AcceptType::Bcs
+        } else {
+            AcceptType::Json
+        }
+    }
+}
+
+// Extracts the response type negotiated through the Accept header, so a single
+// route can serve both the JSON and the BCS representation.
+pub fn accept_type() -> impl Filter<Extract = (AcceptType,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(|headers: HeaderMap| AcceptType::from_headers(&headers))
+}
@@ -2,16 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
     context::Context,
+    etag::with_etag,
     failpoint::fail_point,
+    index::get_or_head,
     metrics::metrics,
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
     version::Version,
 };
 
 use aptos_api_types::{
-    AccountData, Address, AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response,
-    TransactionId,
+    AccountData, Address, AptosErrorCode, AsConverter, Error, LedgerInfo, MoveModuleBytecode,
+    Response, TransactionId,
 };
 use aptos_types::{
     account_config::AccountResource,
@@ -27,13 +30,14 @@ use move_deps::move_core_types::{
     move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
+use std::{collections::BTreeMap, convert::TryInto};
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
+// HEAD /accounts/<address>
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam)
-        .and(warp::get())
+        .and(get_or_head())
         .and(context.filter())
         .and_then(handle_get_account)
         .with(metrics("get_account"))
@@ -41,22 +45,31 @@ pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
 }
 
 // GET /accounts/<address>/resources
+// HEAD /accounts/<address>/resources
+// Replies with BCS if the Accept header asks for it, and JSON otherwise. The BCS
+// reply maps each resource type to the raw bytes of the resource.
 pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("accounts" / AddressParam / "resources")
-        .and(warp::get())
-        .and(context.filter())
-        .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
-        .untuple_one()
-        .and_then(handle_get_account_resources)
-        .with(metrics("get_account_resources"))
-        .boxed()
+    with_etag(
+        warp::path!("accounts" / AddressParam / "resources")
+            .and(get_or_head())
+            .and(context.filter())
+            .and(warp::query::<Version>())
+            .and(accept_type())
+            .map(|address, ctx, version: Version, accept_type| {
+                (version.version, address, ctx, accept_type)
+            })
+            .untuple_one()
+            .and_then(handle_get_account_resources),
+    )
+    .with(metrics("get_account_resources"))
+    .boxed()
 }
 
 // GET /accounts/<address>/modules
+// HEAD /accounts/<address>/modules
 pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "modules")
-        .and(warp::get())
+        .and(get_or_head())
         .and(context.filter())
         .and(warp::query::<Version>())
         .map(|address, ctx, version: Version| (version.version, address, ctx))
@@ -78,9 +91,10 @@ async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     context: Context,
+    accept_type: AcceptType,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    Ok(Account::new(ledger_version, address, context)?.resources(accept_type)?)
 }
 
 async fn handle_get_account_modules(
@@ -115,6 +129,13 @@ impl Account {
                 "ledger",
                 TransactionId::Version(ledger_version),
                 latest_ledger_info.version(),
+            )
+            .error_code(AptosErrorCode::VersionNotFound));
+        } else if ledger_version < latest_ledger_info.oldest_version() {
+            return Err(Error::version_pruned(
+                ledger_version,
+                latest_ledger_info.oldest_version(),
+                latest_ledger_info.version(),
             ));
         }
 
@@ -147,13 +168,25 @@ impl Account {
         Response::new(self.latest_ledger_info, &account)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
-        let resources = self
-            .context
-            .move_resolver()?
-            .as_converter()
-            .try_into_resources(self.account_state()?.get_resources())?;
-        Response::new(self.latest_ledger_info, &resources)
+    pub fn resources(self, accept_type: AcceptType) -> Result<impl Reply, Error> {
+        let account_state = self.account_state()?;
+        match accept_type {
+            AcceptType::Json => {
+                let resources = self
+                    .context
+                    .move_resolver()?
+                    .as_converter()
+                    .try_into_resources(account_state.get_resources())?;
+                Response::new(self.latest_ledger_info, &resources)
+            }
+            AcceptType::Bcs => {
+                let resources: BTreeMap<StructTag, Vec<u8>> = account_state
+                    .get_resources()
+                    .map(|(struct_tag, bytes)| (struct_tag, bytes.to_vec()))
+                    .collect();
+                Response::new_bcs(self.latest_ledger_info, &resources)
+            }
+        }
     }
 
     pub fn modules(self) -> Result<impl Reply, Error> {
@@ -226,6 +259,7 @@ impl Account {
             ),
             self.latest_ledger_info.version(),
         )
+        .error_code(AptosErrorCode::AccountNotFound)
     }
 
     fn resource_not_found(&self, struct_tag: &StructTag) -> Error {
@@ -237,6 +271,7 @@ impl Account {
             ),
             self.latest_ledger_info.version(),
         )
+        .error_code(AptosErrorCode::ResourceNotFound)
     }
 
     fn field_not_found(&self, struct_tag: &StructTag, field_name: &Identifier) -> Error {
@@ -248,5 +283,6 @@ impl Account {
             ),
             self.latest_ledger_info.version(),
         )
+        .error_code(AptosErrorCode::ResourceNotFound)
     }
 }
@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use flate2::{write::GzEncoder, Compression};
+use hyper::{body, Body};
+use std::{convert::Infallible, io::Write};
+use warp::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    reply::Response,
+    Filter, Reply,
+};
+
+const GZIP: &str = "gzip";
+
+// Compress the replies of the given filter with gzip when the client says it
+// accepts it via the Accept-Encoding header. This works on the final response,
+// so it applies equally to JSON and BCS endpoints, as well as error replies.
+pub fn gzip<F, R>(filter: F) -> impl Filter<Extract = (Response,), Error = Infallible> + Clone
+where
+    F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    warp::header::headers_cloned()
+        .and(filter)
+        .and_then(|request_headers: HeaderMap, reply: R| {
+            let response = reply.into_response();
+            async move { Ok::<_, Infallible>(compress(&request_headers, response).await) }
+        })
+}
+
+// Returns true if the Accept-Encoding header allows gzip, i.e. it lists gzip
+// or `*` without a zero quality value.
+fn accepts_gzip(request_headers: &HeaderMap) -> bool {
+    request_headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|encoding| {
+            let mut params = encoding.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let rejected = params.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    == Some(0.0)
+            });
+            (name.eq_ignore_ascii_case(GZIP) || name == "*") && !rejected
+        })
+}
+
+async fn compress(request_headers: &HeaderMap, response: Response) -> Response {
+    let (mut parts, response_body) = response.into_parts();
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+    if !accepts_gzip(request_headers) || parts.headers.contains_key(header::CONTENT_ENCODING) {
+        return Response::from_parts(parts, response_body);
+    }
+
+    let bytes = match body::to_bytes(response_body).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let mut response = Response::default();
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            return response;
+        }
+    };
+    if bytes.is_empty() {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    match encoder.write_all(&bytes).and_then(|_| encoder.finish()) {
+        Ok(compressed) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            // The compressed body isn't byte for byte the same, so only a weak
+            // validator still holds for it.
+            let weak_etag = parts
+                .headers
+                .get(header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .filter(|etag| !etag.starts_with("W/"))
+                .and_then(|etag| HeaderValue::from_str(&format!("W/{}", etag)).ok());
+            if let Some(weak_etag) = weak_etag {
+                parts.headers.insert(header::ETAG, weak_etag);
+            }
+            parts
+                .headers
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static(GZIP));
+            Response::from_parts(parts, Body::from(compressed))
+        }
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
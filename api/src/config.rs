@@ -0,0 +1,52 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    page::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+};
+use aptos_api_types::Response;
+use aptos_config::config::RoleType;
+use serde::Serialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// The API settings in effect on this node, returned by GET "/-/config".
+/// This only contains what is safe to share, e.g. it says whether TLS is
+/// enabled but leaves out the certificate and key paths.
+#[derive(Serialize)]
+pub struct ConfigResponse {
+    chain_id: u8,
+    node_role: RoleType,
+    tls_enabled: bool,
+    content_length_limit: u64,
+    cors_allowed_origins: Vec<String>,
+    default_page_size: u16,
+    max_page_size: u16,
+}
+
+// GET /-/config
+pub fn get_config(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("-" / "config")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_config)
+        .with(metrics("get_config"))
+        .boxed()
+}
+
+async fn handle_get_config(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_config")?;
+    let ledger_info = context.get_latest_ledger_info()?;
+    let config_response = ConfigResponse {
+        chain_id: context.chain_id().id(),
+        node_role: context.node_role(),
+        tls_enabled: context.tls_enabled(),
+        content_length_limit: context.content_length_limit(),
+        cors_allowed_origins: context.cors_allowed_origins().to_vec(),
+        default_page_size: DEFAULT_PAGE_SIZE,
+        max_page_size: MAX_PAGE_SIZE,
+    };
+    Ok(Response::new(ledger_info, &config_response)?)
+}
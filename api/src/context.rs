@@ -9,7 +9,7 @@ use aptos_types::{
     account_address::AccountAddress,
     account_state::AccountState,
     chain_id::ChainId,
-    contract_event::ContractEvent,
+    contract_event::EventWithVersion,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionWithProof},
@@ -24,7 +24,11 @@ use aptos_types::{
 };
 use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use futures::{channel::oneshot, SinkExt};
-use std::{convert::Infallible, sync::Arc};
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use storage_interface::state_view::{
     DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
 };
@@ -37,6 +41,7 @@ pub struct Context {
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     node_config: NodeConfig,
+    started_at: Instant,
 }
 
 impl Context {
@@ -51,6 +56,7 @@ impl Context {
             db,
             mp_sender,
             node_config,
+            started_at: Instant::now(),
         }
     }
 
@@ -72,10 +78,23 @@ impl Context {
         self.node_config.base.role
     }
 
+    /// How long the API has been up, i.e. since the context was created.
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
     pub fn content_length_limit(&self) -> u64 {
         self.node_config.api.content_length_limit()
     }
 
+    pub fn tls_enabled(&self) -> bool {
+        self.node_config.api.tls_cert_path.is_some()
+    }
+
+    pub fn cors_allowed_origins(&self) -> &[String] {
+        &self.node_config.api.cors_allowed_origins
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -252,18 +271,21 @@ impl Context {
         start: u64,
         limit: u16,
         ledger_version: u64,
-    ) -> Result<Vec<ContractEvent>> {
+    ) -> Result<Vec<EventWithVersion>> {
         let events = self
             .db
             .get_events(event_key, start, Order::Ascending, limit as u64)?;
         Ok(events
             .into_iter()
             .filter(|event| event.transaction_version <= ledger_version)
-            .map(|event| event.event)
             .collect::<Vec<_>>())
     }
 
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
         super::health_check::health_check_route(self.db.clone())
     }
+
+    pub fn healthz_route(&self) -> BoxedFilter<(impl Reply,)> {
+        super::health_check::healthz_route(self.clone())
+    }
 }
@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use hyper::{body, Body};
+use warp::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    reply::Response,
+    Filter, Rejection, Reply,
+};
+
+// Adds an ETag computed from the body to the successful replies of the given
+// filter, and replies 304 Not Modified instead when the client already has a
+// matching copy according to If-None-Match. The tag only depends on the body,
+// so this is meant for reads that rarely or never change, e.g. a transaction
+// by version, and not for things like the ledger info that change every block.
+pub fn with_etag<F, R>(filter: F) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    warp::header::headers_cloned()
+        .and(filter)
+        .and_then(|request_headers: HeaderMap, reply: R| {
+            let response = reply.into_response();
+            async move { Ok::<_, Rejection>(conditional(&request_headers, response).await) }
+        })
+}
+
+// Returns true if any entity tag in If-None-Match matches the given one. This
+// uses the weak comparison, so a tag that was weakened, e.g. because the body
+// was compressed, still matches.
+fn if_none_match(request_headers: &HeaderMap, etag: &str) -> bool {
+    let opaque_tag = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque_tag(etag);
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque_tag(tag) == etag)
+}
+
+async fn conditional(request_headers: &HeaderMap, response: Response) -> Response {
+    let (mut parts, response_body) = response.into_parts();
+    if parts.status != StatusCode::OK {
+        return Response::from_parts(parts, response_body);
+    }
+
+    let bytes = match body::to_bytes(response_body).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let mut response = Response::default();
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            return response;
+        }
+    };
+    let etag = format!("\"{}\"", HashValue::sha3_256_of(&bytes).to_hex());
+    let not_modified = if_none_match(request_headers, &etag);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+
+    if not_modified {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
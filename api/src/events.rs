@@ -2,104 +2,74 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accept_type::AcceptType,
+    accept_type::{accept_type, AcceptType},
     accounts::Account,
     context::Context,
     failpoint::fail_point,
-    metrics::metrics,
+    metrics::metrics_by_accept_type,
     page::Page,
     param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
 };
 
-use aptos_api_types::{mime_types::BCS, AsConverter, Error, LedgerInfo, Response};
+use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
 
 use anyhow::Result;
-use aptos_types::event::EventKey;
-use warp::{filters::BoxedFilter, http::header::ACCEPT, Filter, Rejection, Reply};
+use aptos_types::{contract_event::ContractEvent, event::EventKey};
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
-// GET /events/<event_key>
-pub fn get_json_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("events" / EventKeyParam)
-        .and(warp::get())
-        .and(warp::query::<Page>())
-        .and(context.filter())
-        .map(|event_key: EventKeyParam, page: Page, context: Context| {
-            (event_key, page, context, AcceptType::Json)
-        })
-        .untuple_one()
-        .and_then(handle_get_events_by_event_key)
-        .with(metrics("get_json_events_by_event_key"))
-        .boxed()
+#[derive(Clone, Debug, Deserialize)]
+struct WithVersions {
+    #[serde(default)]
+    with_versions: bool,
 }
 
 // GET /events/<event_key>
-pub fn get_bcs_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
+// Replies with BCS if the Accept header asks for it, and JSON otherwise. The BCS events
+// carry the version of the transaction that emitted them when `with_versions=true`.
+pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
         .and(warp::get())
-        .and(warp::header::exact(ACCEPT.as_str(), BCS))
         .and(warp::query::<Page>())
+        .and(warp::query::<WithVersions>())
         .and(context.filter())
-        .map(|event_key: EventKeyParam, page: Page, context: Context| {
-            (event_key, page, context, AcceptType::Bcs)
-        })
-        .untuple_one()
+        .and(accept_type())
         .and_then(handle_get_events_by_event_key)
-        .with(metrics("get_bcs_events_by_event_key"))
+        .with(metrics_by_accept_type(
+            "get_json_events_by_event_key",
+            "get_bcs_events_by_event_key",
+        ))
         .boxed()
 }
 
 // GET /accounts/<address>/events/<event_handle_struct>/<field_name>
-pub fn get_json_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
-        .and(warp::get())
-        .and(warp::query::<Page>())
-        .and(context.filter())
-        .map(|address, struct_tag, field_name, page, context| {
-            (
-                address,
-                struct_tag,
-                field_name,
-                page,
-                context,
-                AcceptType::Json,
-            )
-        })
-        .untuple_one()
-        .and_then(handle_get_events_by_event_handle)
-        .with(metrics("get_events_by_event_handle"))
-        .boxed()
-}
-
-pub fn get_bcs_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
+// Replies with BCS if the Accept header asks for it, and JSON otherwise, see
+// get_events_by_event_key.
+pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
         .and(warp::get())
-        .and(warp::header::exact(ACCEPT.as_str(), BCS))
         .and(warp::query::<Page>())
+        .and(warp::query::<WithVersions>())
         .and(context.filter())
-        .map(|address, struct_tag, field_name, page, context| {
-            (
-                address,
-                struct_tag,
-                field_name,
-                page,
-                context,
-                AcceptType::Bcs,
-            )
-        })
-        .untuple_one()
+        .and(accept_type())
         .and_then(handle_get_events_by_event_handle)
-        .with(metrics("get_bcs_events_by_event_handle"))
+        .with(metrics_by_accept_type(
+            "get_events_by_event_handle",
+            "get_bcs_events_by_event_handle",
+        ))
         .boxed()
 }
 
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
+    with_versions: WithVersions,
     context: Context,
     accept_type: AcceptType,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page, accept_type)?)
+    let key = event_key.parse("event key")?.into();
+    Ok(Events::new(key, context)?.list(page, with_versions, accept_type)?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -107,13 +77,14 @@ async fn handle_get_events_by_event_handle(
     struct_tag: MoveStructTagParam,
     field_name: MoveIdentifierParam,
     page: Page,
+    with_versions: WithVersions,
     context: Context,
     accept_type: AcceptType,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
     let key =
         Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page, accept_type)?)
+    Ok(Events::new(key, context)?.list(page, with_versions, accept_type)?)
 }
 
 struct Events {
@@ -132,14 +103,24 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page, accept_type: AcceptType) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
+    pub fn list(
+        self,
+        page: Page,
+        with_versions: WithVersions,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
+        let events = self.context.get_events(
             &self.key,
             page.start(0, u64::MAX)?,
             page.limit()?,
             self.ledger_info.version(),
         )?;
 
+        if with_versions.with_versions && accept_type == AcceptType::Bcs {
+            return Response::new_bcs(self.ledger_info, &events);
+        }
+        let contract_events: Vec<ContractEvent> =
+            events.into_iter().map(|event| event.event).collect();
         match accept_type {
             AcceptType::Json => {
                 let resolver = self.context.move_resolver()?;
@@ -2,13 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #[allow(unused_imports)]
-use anyhow::{format_err, Result};
-use aptos_api_types::Error;
+use anyhow::Result;
+use aptos_api_types::TemporarilyUnavailable;
+use std::time::Duration;
+
+// Injected failures stand in for transient errors, so tell clients to retry.
+#[allow(dead_code)]
+const FAIL_POINT_RETRY_AFTER: Duration = Duration::from_secs(1);
 
 #[allow(unused_variables)]
 #[inline]
-pub fn fail_point(name: &str) -> Result<(), Error> {
+pub fn fail_point(name: &str) -> Result<(), TemporarilyUnavailable> {
     Ok(fail::fail_point!(format!("api::{}", name).as_str(), |_| {
-        Err(format_err!("unexpected internal error for {}", name).into())
+        Err(TemporarilyUnavailable::new(
+            format!("unexpected internal error for {}", name),
+            FAIL_POINT_RETRY_AFTER,
+        ))
     }))
 }
@@ -1,14 +1,22 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::context::Context;
 use anyhow::{ensure, Result};
+use aptos_api_types::LedgerInfo;
+use aptos_config::config::RoleType;
+use serde::Serialize;
 use std::{
     ops::Sub,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use storage_interface::DbReader;
-use warp::{filters::BoxedFilter, reject, Filter, Reply};
+use warp::{filters::BoxedFilter, http::StatusCode, reject, reply, Filter, Reply};
+
+// How far the latest ledger info timestamp may be behind the current time for
+// /healthz to consider the node synced, unless duration_secs says otherwise.
+const DEFAULT_HEALTHZ_DURATION_SECS: u64 = 30;
 
 // HealthCheckParams is optional params for different layer's health check.
 // If no param is provided, server return 200 by default to indicate HTTP server is running health.
@@ -50,6 +58,54 @@ async fn health_check(
     Ok(Box::new("aptos-node:ok"))
 }
 
+/// The data returned by the detailed health check (i.e., GET "/healthz").
+/// The ledger info is flattened into the same map like in the index response.
+#[derive(Serialize)]
+pub struct HealthzResponse {
+    #[serde(flatten)]
+    ledger_info: LedgerInfo,
+    node_role: RoleType,
+    synced: bool,
+    uptime_secs: u64,
+}
+
+// GET /healthz
+// Returns 200 if the node is synced, i.e. the latest ledger info timestamp is
+// at most duration_secs old, and 503 otherwise, along with the details as JSON.
+pub fn healthz_route(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("healthz")
+        .and(warp::get())
+        .and(warp::query().map(move |params: HealthCheckParams| params))
+        .and(context.filter())
+        .and(warp::any().map(SystemTime::now))
+        .and_then(healthz)
+        .boxed()
+}
+
+async fn healthz(
+    params: HealthCheckParams,
+    context: Context,
+    now: SystemTime,
+) -> Result<impl Reply, warp::Rejection> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let duration = params
+        .duration_secs
+        .unwrap_or(DEFAULT_HEALTHZ_DURATION_SECS);
+    let synced = check_latest_ledger_info_timestamp(duration, ledger_info.timestamp(), now).is_ok();
+    let response = HealthzResponse {
+        ledger_info,
+        node_role: context.node_role(),
+        synced,
+        uptime_secs: context.uptime().as_secs(),
+    };
+    let code = if synced {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok(reply::with_status(reply::json(&response), code))
+}
+
 pub fn check_latest_ledger_info_timestamp(
     duration_sec: u64,
     timestamp_usecs: u64,
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    accounts, compression, config,
     context::Context,
     events,
     failpoint::fail_point,
@@ -10,21 +10,30 @@ use crate::{
     metrics::{metrics, status_metrics},
     state, transactions,
 };
-use aptos_api_types::{Error, LedgerInfo, Response};
+use aptos_api_types::{Error, LedgerInfo, Response, TemporarilyUnavailable, X_APTOS_CHAIN_ID};
 use aptos_config::config::RoleType;
+use hyper::Body;
+use once_cell::sync::Lazy;
 use serde::Serialize;
 use std::convert::Infallible;
 use warp::{
     body::BodyDeserializeError,
     cors::CorsForbidden,
     filters::BoxedFilter,
-    http::{header, HeaderValue, StatusCode},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     reject::{LengthRequired, MethodNotAllowed, PayloadTooLarge, UnsupportedMediaType},
     reply, Filter, Rejection, Reply,
 };
 
 const OPEN_API_HTML: &str = include_str!("../doc/spec.html");
-const OPEN_API_SPEC: &str = include_str!("../doc/openapi.yaml");
+pub(crate) const OPEN_API_SPEC: &str = include_str!("../doc/openapi.yaml");
+
+/// The OpenAPI spec converted to JSON, for tools that don't understand YAML.
+static OPEN_API_SPEC_JSON: Lazy<String> = Lazy::new(|| {
+    let spec: serde_json::Value =
+        serde_yaml::from_str(OPEN_API_SPEC).expect("openapi.yaml should be valid YAML");
+    serde_json::to_string(&spec).expect("OpenAPI spec should serialize to JSON")
+});
 
 /// The struct holding all data returned to the client by the
 /// index endpoint (i.e., GET "/"). The data is flattened into
@@ -46,60 +55,123 @@ impl IndexResponse {
 }
 
 pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
-    index(context.clone())
+    let chain_id = context.chain_id().id();
+    let allowed_origins = context.cors_allowed_origins().to_vec();
+    let cors = if allowed_origins.is_empty() {
+        warp::cors().allow_any_origin()
+    } else {
+        warp::cors().allow_origins(allowed_origins.iter().map(String::as_str))
+    };
+
+    let routes = index(context.clone())
         .or(openapi_spec())
         .or(accounts::get_account(context.clone()))
         .or(accounts::get_account_resources(context.clone()))
         .or(accounts::get_account_modules(context.clone()))
-        .or(transactions::get_bcs_transaction(context.clone()))
-        .or(transactions::get_json_transaction(context.clone()))
-        .or(transactions::get_bcs_transactions(context.clone()))
-        .or(transactions::get_json_transactions(context.clone()))
+        .or(transactions::get_transaction(context.clone()))
+        .or(transactions::get_transactions(context.clone()))
         .or(transactions::get_account_transactions(context.clone()))
         .or(transactions::simulate_bcs_transactions(context.clone()))
         .or(transactions::simulate_json_transactions(context.clone()))
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
-        .or(events::get_bcs_events_by_event_key(context.clone()))
-        .or(events::get_json_events_by_event_key(context.clone()))
-        .or(events::get_bcs_events_by_event_handle(context.clone()))
-        .or(events::get_json_events_by_event_handle(context.clone()))
+        .or(events::get_events_by_event_key(context.clone()))
+        .or(events::get_events_by_event_handle(context.clone()))
         .or(state::get_account_resource(context.clone()))
         .or(state::get_account_module(context.clone()))
         .or(state::get_table_item(context.clone()))
+        .or(state::get_raw_state_value(context.clone()))
         .or(context.health_check_route().with(metrics("health_check")))
+        .or(context.healthz_route().with(metrics("healthz")))
+        .or(config::get_config(context.clone()))
         .with(
-            warp::cors()
-                .allow_any_origin()
-                .allow_methods(vec!["POST", "GET"])
+            cors.allow_methods(vec!["POST", "GET"])
                 .allow_headers(vec![header::CONTENT_TYPE]),
         )
         .recover(handle_rejection)
-        .with(log::logger())
-        .with(status_metrics())
+        // Applied after recovering so that error replies carry it too, letting
+        // clients notice they're talking to the wrong network even on a 4xx.
+        .with(warp::reply::with::header(
+            X_APTOS_CHAIN_ID,
+            chain_id.to_string(),
+        ));
+
+    // Rejections are handled outside of the CORS filter, so add the header
+    // to those replies here, respecting the allowed origins.
+    let routes =
+        warp::header::headers_cloned()
+            .and(routes)
+            .map(move |request_headers: HeaderMap, reply| {
+                let mut response = Reply::into_response(reply);
+                if !response
+                    .headers()
+                    .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                {
+                    if let Some(origin) = cors_allow_origin(&allowed_origins, &request_headers) {
+                        response
+                            .headers_mut()
+                            .insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+                    }
+                }
+                response
+            });
+
+    // Read endpoints accept HEAD so monitoring can check the X-Aptos-* headers
+    // cheaply. They're handled like GET, so drop the body before it's sent.
+    let routes = warp::method().and(routes).map(|method: Method, reply| {
+        let mut response = Reply::into_response(reply);
+        if method == Method::HEAD {
+            *response.body_mut() = Body::empty();
+        }
+        response
+    });
+
+    log::logger(compression::gzip(routes)).with(status_metrics())
 }
 
 // GET /openapi.yaml
+// GET /spec.json
 // GET /spec.html
 pub fn openapi_spec() -> BoxedFilter<(impl Reply,)> {
+    // Convert the spec now so a broken spec fails at startup rather than on request.
+    Lazy::force(&OPEN_API_SPEC_JSON);
+
     let spec = warp::path!("openapi.yaml")
         .and(warp::get())
         .map(|| OPEN_API_SPEC)
         .with(metrics("openapi_yaml"))
         .boxed();
+    let json = warp::path!("spec.json")
+        .and(warp::get())
+        .map(|| {
+            reply::with_header(
+                OPEN_API_SPEC_JSON.as_str(),
+                header::CONTENT_TYPE,
+                "application/json",
+            )
+        })
+        .with(metrics("openapi_json"))
+        .boxed();
     let html = warp::path!("spec.html")
         .and(warp::get())
         .map(|| reply::html(open_api_html()))
         .with(metrics("spec_html"))
         .boxed();
-    spec.or(html).boxed()
+    spec.or(json).or(html).boxed()
+}
+
+// Matches both GET and HEAD requests, for read endpoints that should also
+// serve their headers without a body.
+pub(crate) fn get_or_head() -> BoxedFilter<()> {
+    warp::get().or(warp::head()).unify().boxed()
 }
 
 // GET /
+// HEAD /
 pub fn index(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path::end()
-        .and(warp::get())
+        .and(get_or_head())
         .and(context.filter())
         .and_then(handle_index)
         .with(metrics("get_ledger_info"))
@@ -117,6 +189,7 @@ pub async fn handle_index(context: Context) -> Result<impl Reply, Rejection> {
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let code;
     let body;
+    let mut retry_after = None;
 
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
@@ -124,6 +197,10 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     } else if let Some(error) = err.find::<Error>() {
         code = error.status_code();
         body = reply::json(error);
+    } else if let Some(cause) = err.find::<TemporarilyUnavailable>() {
+        code = cause.status_code();
+        body = reply::json(&Error::new(code, cause.message.clone()));
+        retry_after = Some(cause.retry_after_secs());
     } else if let Some(cause) = err.find::<CorsForbidden>() {
         code = StatusCode::FORBIDDEN;
         body = reply::json(&Error::new(code, cause.to_string()));
@@ -147,11 +224,30 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
         body = reply::json(&Error::new(code, format!("unexpected error: {:?}", err)));
     }
     let mut rep = reply::with_status(body, code).into_response();
-    rep.headers_mut()
-        .insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    if let Some(secs) = retry_after {
+        rep.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    }
     Ok(rep)
 }
 
+// Returns the value of the access-control-allow-origin header for a request,
+// which is `*` if any origin is allowed, or the origin of the request if it is
+// in the list of allowed origins.
+fn cors_allow_origin(
+    allowed_origins: &[String],
+    request_headers: &HeaderMap,
+) -> Option<HeaderValue> {
+    if allowed_origins.is_empty() {
+        return Some(HeaderValue::from_static("*"));
+    }
+    let origin = request_headers.get(header::ORIGIN)?;
+    allowed_origins
+        .iter()
+        .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+        .then(|| origin.clone())
+}
+
 fn open_api_html() -> String {
     OPEN_API_HTML.replace("hideTryIt=\"true\"", "")
 }
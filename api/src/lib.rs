@@ -3,7 +3,10 @@
 
 mod accept_type;
 mod accounts;
+mod compression;
+mod config;
 pub mod context;
+mod etag;
 mod events;
 mod health_check;
 mod index;
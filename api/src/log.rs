@@ -1,7 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::time::Duration;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 use aptos_logger::{
     debug, error,
@@ -9,38 +13,89 @@ use aptos_logger::{
     sample::Sampling,
     Schema,
 };
+use rand::Rng;
 use warp::{
-    http::header,
-    log::{custom, Info, Log},
+    http::{header, HeaderMap, HeaderValue, Method},
+    path::FullPath,
+    reply::Response,
+    Filter, Reply,
 };
 
-pub fn logger() -> Log<impl Fn(Info) + Copy> {
-    let func = move |info: Info| {
-        let status = info.status().as_u16();
-        let log = HttpRequestLog {
-            remote_addr: info.remote_addr(),
-            method: info.method().to_string(),
-            path: info.path().to_string(),
-            status,
-            referer: info.referer(),
-            user_agent: info.user_agent(),
-            elapsed: info.elapsed(),
-            forwarded: info
-                .request_headers()
-                .get(header::FORWARDED)
-                .and_then(|v| v.to_str().ok()),
-        };
-        if status >= 500 {
-            sample!(SampleRate::Duration(Duration::from_secs(1)), error!(log));
-        } else {
-            debug!(log);
-        }
-    };
-    custom(func)
+pub const X_REQUEST_ID: &str = "X-Request-Id";
+
+// Longest client supplied request id we're willing to echo back and log.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+// Logs every request handled by the given filter. Each request is tagged with
+// a request id, which is returned in the X-Request-Id response header and
+// included in the log line, so client reports can be matched with server logs.
+// A request id supplied by the client is reused, otherwise a new one is made.
+pub fn logger<F, R>(filter: F) -> impl Filter<Extract = (Response,), Error = Infallible> + Clone
+where
+    F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    warp::addr::remote()
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(warp::header::headers_cloned())
+        .and(warp::any().map(Instant::now))
+        .and(filter)
+        .map(
+            |remote_addr: Option<SocketAddr>,
+             method: Method,
+             path: FullPath,
+             request_headers: HeaderMap,
+             start: Instant,
+             reply: R| {
+                let request_id = request_id(&request_headers);
+                let mut response = reply.into_response();
+                let status = response.status().as_u16();
+                let log = HttpRequestLog {
+                    request_id: &request_id,
+                    remote_addr,
+                    method: method.to_string(),
+                    path: path.as_str().to_string(),
+                    status,
+                    referer: header_str(&request_headers, header::REFERER),
+                    user_agent: header_str(&request_headers, header::USER_AGENT),
+                    elapsed: start.elapsed(),
+                    forwarded: header_str(&request_headers, header::FORWARDED),
+                };
+                if status >= 500 {
+                    sample!(SampleRate::Duration(Duration::from_secs(1)), error!(log));
+                } else {
+                    debug!(log);
+                }
+
+                if let Ok(value) = HeaderValue::from_str(&request_id) {
+                    response.headers_mut().insert(X_REQUEST_ID, value);
+                }
+                response
+            },
+        )
+}
+
+fn request_id(request_headers: &HeaderMap) -> String {
+    request_headers
+        .get(X_REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= MAX_REQUEST_ID_LEN
+                && id.chars().all(|c| c.is_ascii_graphic())
+        })
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| format!("{:032x}", rand::thread_rng().gen::<u128>()))
+}
+
+fn header_str(request_headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    request_headers.get(name).and_then(|v| v.to_str().ok())
 }
 
 #[derive(Schema)]
 pub struct HttpRequestLog<'a> {
+    request_id: &'a str,
     #[schema(display)]
     remote_addr: Option<std::net::SocketAddr>,
     method: String,
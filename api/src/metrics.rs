@@ -1,6 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::accept_type::AcceptType;
+
 use aptos_metrics_core::{register_histogram_vec, HistogramVec};
 
 use once_cell::sync::Lazy;
@@ -28,18 +30,36 @@ pub static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
 // The operation_id is the id for the request handler.
 // Should use same `operationId` defined in `openapi.yaml` whenever possible.
 pub fn metrics(operation_id: &'static str) -> Log<impl Fn(Info) + Copy> {
+    let func = move |info: Info| observe(&info, operation_id);
+    custom(func)
+}
+
+// Same as `metrics`, for routes that reply with JSON or BCS depending on the
+// Accept header. Each response type is recorded under its own operation_id.
+pub fn metrics_by_accept_type(
+    json_operation_id: &'static str,
+    bcs_operation_id: &'static str,
+) -> Log<impl Fn(Info) + Copy> {
     let func = move |info: Info| {
-        HISTOGRAM
-            .with_label_values(&[
-                info.method().to_string().as_str(),
-                operation_id,
-                info.status().as_u16().to_string().as_str(),
-            ])
-            .observe(info.elapsed().as_secs_f64());
+        let operation_id = match AcceptType::from_headers(info.request_headers()) {
+            AcceptType::Json => json_operation_id,
+            AcceptType::Bcs => bcs_operation_id,
+        };
+        observe(&info, operation_id)
     };
     custom(func)
 }
 
+fn observe(info: &Info, operation_id: &str) {
+    HISTOGRAM
+        .with_label_values(&[
+            info.method().to_string().as_str(),
+            operation_id,
+            info.status().as_u16().to_string().as_str(),
+        ])
+        .observe(info.elapsed().as_secs_f64());
+}
+
 // Record metrics by response status.
 // This is for understanding the overview of responses in case server
 // is overloaded by unknown reason.
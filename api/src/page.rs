@@ -3,14 +3,14 @@
 
 use crate::param::{Param, TransactionVersionParam};
 
-use aptos_api_types::{Error, TransactionId};
+use aptos_api_types::{AptosErrorCode, Error, TransactionId};
 
 use anyhow::Result;
 use serde::Deserialize;
 use std::num::NonZeroU16;
 
-const DEFAULT_PAGE_SIZE: u16 = 25;
-const MAX_PAGE_SIZE: u16 = 1000;
+pub(crate) const DEFAULT_PAGE_SIZE: u16 = 25;
+pub(crate) const MAX_PAGE_SIZE: u16 = 1000;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
@@ -26,11 +26,10 @@ impl Page {
             .map(|v| v.parse("start"))
             .unwrap_or_else(|| Ok(default))?;
         if version > max {
-            return Err(Error::not_found(
-                "transaction",
-                TransactionId::Version(version),
-                max,
-            ));
+            return Err(
+                Error::not_found("transaction", TransactionId::Version(version), max)
+                    .error_code(AptosErrorCode::TransactionNotFound),
+            );
         }
         Ok(version)
     }
@@ -3,16 +3,20 @@
 
 use crate::{
     context::Context,
+    etag::with_etag,
     failpoint::fail_point,
+    index::get_or_head,
     metrics::metrics,
     param::{
-        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, TableHandleParam,
+        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, StateKeyParam,
+        TableHandleParam,
     },
     version::Version,
 };
 use anyhow::anyhow;
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest, TransactionId,
+    AptosErrorCode, AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest,
+    TransactionId,
 };
 use aptos_state_view::StateView;
 use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
@@ -27,24 +31,28 @@ use storage_interface::state_view::DbStateView;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
 // GET /accounts/<address>/resource/<resource_type>
+// HEAD /accounts/<address>/resource/<resource_type>
 pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("accounts" / AddressParam / "resource" / MoveStructTagParam)
-        .and(warp::get())
-        .and(context.filter())
-        .and(warp::query::<Version>())
-        .map(|address, struct_tag, ctx, version: Version| {
-            (version.version, address, struct_tag, ctx)
-        })
-        .untuple_one()
-        .and_then(handle_get_account_resource)
-        .with(metrics("get_account_resource"))
-        .boxed()
+    with_etag(
+        warp::path!("accounts" / AddressParam / "resource" / MoveStructTagParam)
+            .and(get_or_head())
+            .and(context.filter())
+            .and(warp::query::<Version>())
+            .map(|address, struct_tag, ctx, version: Version| {
+                (version.version, address, struct_tag, ctx)
+            })
+            .untuple_one()
+            .and_then(handle_get_account_resource),
+    )
+    .with(metrics("get_account_resource"))
+    .boxed()
 }
 
 // GET /state/module/<address>/<module_name>
+// HEAD /state/module/<address>/<module_name>
 pub fn get_account_module(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "module" / MoveIdentifierParam)
-        .and(warp::get())
+        .and(get_or_head())
         .and(context.filter())
         .and(warp::query::<Version>())
         .map(|address, name, ctx, version: Version| (version.version, address, name, ctx))
@@ -71,6 +79,19 @@ pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /state/raw/<bcs_encoded_state_key>
+pub fn get_raw_state_value(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("state" / "raw" / StateKeyParam)
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|state_key, ctx, version: Version| (version.version, state_key, ctx))
+        .untuple_one()
+        .and_then(handle_get_raw_state_value)
+        .with(metrics("get_raw_state_value"))
+        .boxed()
+}
+
 async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
@@ -111,6 +132,18 @@ async fn handle_get_table_item(
     Ok(State::new(ledger_version, context)?.table_item(handle.parse("table handle")?, body)?)
 }
 
+async fn handle_get_raw_state_value(
+    ledger_version: Option<LedgerVersionParam>,
+    state_key: StateKeyParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_raw_state_value")?;
+    let state_key_bytes: Vec<u8> = state_key.parse("state key")?.into();
+    let state_key: StateKey = bcs::from_bytes(&state_key_bytes)
+        .map_err(|_| Error::invalid_param("state key", hex::encode(&state_key_bytes)))?;
+    Ok(State::new(ledger_version, context)?.raw_state_value(state_key)?)
+}
+
 pub(crate) struct State {
     state_view: DbStateView,
     ledger_version: aptos_types::transaction::Version,
@@ -132,6 +165,13 @@ impl State {
                 "ledger",
                 TransactionId::Version(ledger_version),
                 latest_ledger_info.version(),
+            )
+            .error_code(AptosErrorCode::VersionNotFound));
+        } else if ledger_version < latest_ledger_info.oldest_version() {
+            return Err(Error::version_pruned(
+                ledger_version,
+                latest_ledger_info.oldest_version(),
+                latest_ledger_info.version(),
             ));
         }
 
@@ -155,7 +195,10 @@ impl State {
         let bytes = self
             .state_view
             .get_state_value(&state_key)?
-            .ok_or_else(|| Error::not_found("Resource", resource_key, self.ledger_version))?;
+            .ok_or_else(|| {
+                Error::not_found("Resource", resource_key, self.ledger_version)
+                    .error_code(AptosErrorCode::ResourceNotFound)
+            })?;
 
         let resource = self
             .state_view
@@ -172,7 +215,10 @@ impl State {
         let bytes = self
             .state_view
             .get_state_value(&state_key)?
-            .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
+            .ok_or_else(|| {
+                Error::not_found("Module", module_id, self.ledger_version)
+                    .error_code(AptosErrorCode::ModuleNotFound)
+            })?;
 
         let module = MoveModuleBytecode::new(bytes)
             .try_parse_abi()
@@ -180,6 +226,21 @@ impl State {
         Response::new(self.latest_ledger_info, &module)
     }
 
+    pub fn raw_state_value(self, state_key: StateKey) -> Result<impl Reply, Error> {
+        let bytes = self
+            .state_view
+            .get_state_value(&state_key)?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "State value",
+                    format!("{:?}", state_key),
+                    self.ledger_version,
+                )
+                .error_code(AptosErrorCode::StateValueNotFound)
+            })?;
+        Ok(Response::new_raw_bcs(self.latest_ledger_info, bytes))
+    }
+
     pub fn table_item(self, handle: u128, body: TableItemRequest) -> Result<impl Reply, Error> {
         let TableItemRequest {
             key_type,
@@ -204,7 +265,10 @@ impl State {
         let bytes = self
             .state_view
             .get_state_value(&state_key)?
-            .ok_or_else(|| Error::not_found("table handle or item", key, self.ledger_version))?;
+            .ok_or_else(|| {
+                Error::not_found("table handle or item", key, self.ledger_version)
+                    .error_code(AptosErrorCode::TableItemNotFound)
+            })?;
 
         let move_value = converter.try_into_move_value(&value_type, &bytes)?;
         Response::new(self.latest_ledger_info, &move_value)
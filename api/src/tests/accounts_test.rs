@@ -5,7 +5,11 @@ use crate::{
     current_function_name,
     tests::{find_value, new_test_context},
 };
+use aptos_api_types::mime_types;
+use aptos_types::account_config::{aptos_root_address, AccountResource};
+use move_deps::move_core_types::{language_storage::StructTag, move_resource::MoveStructType};
 use serde_json::json;
+use std::collections::BTreeMap;
 
 #[tokio::test]
 async fn test_get_account_resources_returns_empty_array_for_account_has_no_resources() {
@@ -204,6 +208,23 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resources_bcs() {
+    let context = new_test_context(current_function_name!());
+    let req = warp::test::request()
+        .method("GET")
+        .path(&account_resources_with_ledger_version("0xa550c18", 0))
+        .header("accept", mime_types::BCS);
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], mime_types::BCS);
+
+    let resources: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(resp.body()).unwrap();
+    let account: AccountResource =
+        bcs::from_bytes(&resources[&AccountResource::struct_tag()]).unwrap();
+    assert_eq!(account.address(), aptos_root_address());
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::mime_types;
+use aptos_types::contract_event::{ContractEvent, EventWithVersion};
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
 static EVENT_KEY: &str =
@@ -26,6 +28,41 @@ async fn test_get_events_filter_by_start_sequence_number() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_events_bcs() {
+    let context = new_test_context(current_function_name!());
+    let req = warp::test::request()
+        .method("GET")
+        .path(format!("/events/{}", EVENT_KEY).as_str())
+        .header("accept", mime_types::BCS);
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], mime_types::BCS);
+    let events: Vec<ContractEvent> = bcs::from_bytes(resp.body()).unwrap();
+    assert!(!events.is_empty());
+
+    let req = warp::test::request()
+        .method("GET")
+        .path(format!("/events/{}?with_versions=true", EVENT_KEY).as_str())
+        .header("accept", mime_types::BCS);
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], mime_types::BCS);
+
+    // All the events of the test ledger are emitted by the genesis.
+    let events_with_versions: Vec<EventWithVersion> = bcs::from_bytes(resp.body()).unwrap();
+    assert!(events_with_versions
+        .iter()
+        .all(|event| event.transaction_version == 0));
+    assert_eq!(
+        events_with_versions
+            .into_iter()
+            .map(|event| event.event)
+            .collect::<Vec<_>>(),
+        events
+    );
+}
+
 // turn it back until we have multiple events in genesis
 #[ignore]
 #[tokio::test]
@@ -1,8 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{current_function_name, tests::new_test_context};
+use crate::{
+    context::Context,
+    current_function_name,
+    index::{self, OPEN_API_SPEC},
+    log::X_REQUEST_ID,
+    tests::new_test_context,
+};
+use aptos_api_types::{X_APTOS_CHAIN_ID, X_APTOS_LEDGER_VERSION};
+use aptos_config::config::NodeConfig;
+use aptos_types::chain_id::ChainId;
+use flate2::read::GzDecoder;
 use serde_json::json;
+use std::io::Read;
 
 #[tokio::test]
 async fn test_get_index() {
@@ -37,10 +48,53 @@ async fn test_health_check() {
     assert_eq!(resp.status(), 200)
 }
 
+#[tokio::test]
+async fn test_healthz() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/healthz"))
+        .await;
+    // The test ledger only has the genesis transaction, which is far in the past
+    assert_eq!(resp.status(), 503);
+    let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["synced"], false);
+    assert_eq!(body["chain_id"], 4);
+    assert_eq!(body["ledger_version"], "0");
+    assert_eq!(body["node_role"], "validator");
+    assert!(body["uptime_secs"].is_u64());
+}
+
+#[tokio::test]
+async fn test_get_config() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.tls_cert_path = Some("/secret/cert.pem".to_string());
+    node_config.api.tls_key_path = Some("/secret/key.pem".to_string());
+    node_config.api.content_length_limit = Some(1024);
+    let test_context = new_test_context(current_function_name!());
+    let routes = index::routes(Context::new(
+        ChainId::test(),
+        test_context.db.clone(),
+        test_context.mempool.ac_client.clone(),
+        node_config,
+    ));
+
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/-/config")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["tls_enabled"], true);
+    assert_eq!(body["content_length_limit"], 1024);
+    assert_eq!(body["max_page_size"], 1000);
+    assert!(!String::from_utf8_lossy(resp.body()).contains("/secret/"));
+}
+
 #[tokio::test]
 async fn test_openapi_spec() {
     let context = new_test_context(current_function_name!());
-    let paths = ["/openapi.yaml", "/spec.html"];
+    let paths = ["/openapi.yaml", "/spec.json", "/spec.html"];
     for path in paths {
         let req = warp::test::request().method("GET").path(path);
         let resp = context.reply(req).await;
@@ -48,6 +102,19 @@ async fn test_openapi_spec() {
     }
 }
 
+#[tokio::test]
+async fn test_openapi_spec_json() {
+    let context = new_test_context(current_function_name!());
+    let req = warp::test::request().method("GET").path("/spec.json");
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+
+    let spec: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    let yaml_spec: serde_json::Value = serde_yaml::from_str(OPEN_API_SPEC).unwrap();
+    assert_eq!(spec, yaml_spec);
+}
+
 #[tokio::test]
 async fn test_cors() {
     let context = new_test_context(current_function_name!());
@@ -114,3 +181,181 @@ async fn test_cors_on_non_200_responses() {
     let cors_header = resp.headers().get("access-control-allow-origin").unwrap();
     assert_eq!(cors_header, "*");
 }
+
+#[tokio::test]
+async fn test_gzip_when_accepted() {
+    let context = new_test_context(current_function_name!());
+    let plain_resp = context
+        .reply(warp::test::request().method("GET").path("/"))
+        .await;
+    assert!(plain_resp.headers().get("content-encoding").is_none());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("accept-encoding", "deflate, gzip;q=0.8")
+                .header("origin", "test")
+                .method("GET")
+                .path("/"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-encoding"], "gzip");
+    assert_eq!(resp.headers()["access-control-allow-origin"], "*");
+
+    let mut body = vec![];
+    GzDecoder::new(resp.body().as_ref())
+        .read_to_end(&mut body)
+        .unwrap();
+    let plain_body: serde_json::Value = serde_json::from_slice(plain_resp.body()).unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body, plain_body);
+
+    // Errors are compressed too
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("accept-encoding", "gzip")
+                .method("GET")
+                .path("/accounts/nope/resources"),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.headers()["content-encoding"], "gzip");
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("accept-encoding", "gzip;q=0")
+                .method("GET")
+                .path("/"),
+        )
+        .await;
+    assert!(resp.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_cors_allowed_origins() {
+    let test_context = new_test_context(current_function_name!());
+    let mut node_config = NodeConfig::default();
+    node_config.api.cors_allowed_origins = vec!["https://allowed.example".to_string()];
+    let routes = index::routes(Context::new(
+        ChainId::test(),
+        test_context.db.clone(),
+        test_context.mempool.ac_client.clone(),
+        node_config,
+    ));
+
+    let preflight = |origin: &str| {
+        warp::test::request()
+            .header("origin", origin)
+            .header("Access-Control-Request-Headers", "Content-Type")
+            .header("Access-Control-Request-Method", "GET")
+            .method("OPTIONS")
+            .path("/")
+    };
+    let resp = preflight("https://allowed.example").reply(&routes).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["access-control-allow-origin"],
+        "https://allowed.example"
+    );
+    let resp = preflight("https://other.example").reply(&routes).await;
+    assert_eq!(resp.status(), 403);
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+    // Rejections only carry the header for allowed origins
+    let request = |origin: &str| {
+        warp::test::request()
+            .header("origin", origin)
+            .method("GET")
+            .path("/accounts/nope/resources")
+    };
+    let resp = request("https://allowed.example").reply(&routes).await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(
+        resp.headers()["access-control-allow-origin"],
+        "https://allowed.example"
+    );
+    let resp = request("https://other.example").reply(&routes).await;
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn test_chain_id_header() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/spec.html"))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[X_APTOS_CHAIN_ID], "4");
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/accounts/nope/resources"),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.headers()[X_APTOS_CHAIN_ID], "4");
+}
+
+#[tokio::test]
+async fn test_request_id_header() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header(X_REQUEST_ID, "client-request-1")
+                .method("GET")
+                .path("/"),
+        )
+        .await;
+    assert_eq!(resp.headers()[X_REQUEST_ID], "client-request-1");
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/accounts/nope/resources"),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+    let request_id = resp.headers()[X_REQUEST_ID].to_str().unwrap();
+    assert_eq!(request_id.len(), 32);
+
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/"))
+        .await;
+    assert_ne!(resp.headers()[X_REQUEST_ID], request_id);
+}
+
+#[tokio::test]
+async fn test_head_requests() {
+    let context = new_test_context(current_function_name!());
+    for path in ["/", "/accounts/0x1", "/accounts/0x1/resources"] {
+        let get = context
+            .reply(warp::test::request().method("GET").path(path))
+            .await;
+        let head = context
+            .reply(warp::test::request().method("HEAD").path(path))
+            .await;
+        assert_eq!(head.status(), 200);
+        assert!(head.body().is_empty());
+        assert!(!get.body().is_empty());
+        assert_eq!(
+            head.headers()[X_APTOS_LEDGER_VERSION],
+            get.headers()[X_APTOS_LEDGER_VERSION]
+        );
+        assert_eq!(
+            head.headers()["content-type"],
+            get.headers()["content-type"]
+        );
+    }
+
+    let resp = context
+        .reply(warp::test::request().method("HEAD").path("/transactions"))
+        .await;
+    assert_eq!(resp.status(), 405);
+}
@@ -6,7 +6,19 @@ use crate::{
     tests::{new_test_context, TestContext},
 };
 use aptos_sdk::types::LocalAccount;
-use move_deps::{move_core_types::account_address::AccountAddress, move_package::BuildConfig};
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{aptos_root_address, AccountResource},
+    state_store::state_key::StateKey,
+};
+use move_deps::{
+    move_core_types::{
+        account_address::AccountAddress, language_storage::ResourceKey,
+        move_resource::MoveStructType,
+    },
+    move_package::BuildConfig,
+};
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::{convert::TryInto, path::PathBuf};
@@ -176,6 +188,46 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test]
+async fn test_get_raw_state_value() {
+    let context = new_test_context(current_function_name!());
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        aptos_root_address(),
+        AccountResource::struct_tag(),
+    )));
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&get_raw_state_value(&state_key)),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let expected = context
+        .latest_state_view()
+        .get_state_value(&state_key)
+        .unwrap()
+        .unwrap();
+    assert_eq!(resp.body().to_vec(), expected);
+}
+
+#[tokio::test]
+async fn test_get_raw_state_value_not_found() {
+    let context = new_test_context(current_function_name!());
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::random(),
+        AccountResource::struct_tag(),
+    )));
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&get_raw_state_value(&state_key)),
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -188,6 +240,13 @@ fn get_table_item(handle: u128) -> String {
     format!("/tables/{}/item", handle)
 }
 
+fn get_raw_state_value(state_key: &StateKey) -> String {
+    format!(
+        "/state/raw/0x{}",
+        hex::encode(bcs::to_bytes(state_key).unwrap())
+    )
+}
+
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     let module = build_test_module(account.address()).await;
 
@@ -6,7 +6,7 @@ use crate::{
     tests::{assert_json, new_test_context, pretty, TestContext},
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{mime_types, HexEncodedBytes};
 use aptos_crypto::{
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
@@ -1165,6 +1165,83 @@ async fn test_create_signing_message_rejects_no_content_length_request() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_transaction_by_version_etag() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/transactions/0"))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["vary"], "accept");
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("if-none-match", &etag)
+                .method("GET")
+                .path("/transactions/0"),
+        )
+        .await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(resp.headers()["etag"], etag.as_str());
+    assert_eq!(resp.headers()["vary"], "accept");
+    assert!(resp.body().is_empty());
+
+    // A compressed reply gets a weak tag, which should match as well
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("accept-encoding", "gzip")
+                .header("if-none-match", format!("W/{}", etag))
+                .method("GET")
+                .path("/transactions/0"),
+        )
+        .await;
+    assert_eq!(resp.status(), 304);
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .header("if-none-match", "\"something-else\"")
+                .method("GET")
+                .path("/transactions/0"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.body().is_empty());
+
+    // The ledger info changes every block, so it shouldn't be cached
+    let resp = context
+        .reply(warp::test::request().method("GET").path("/"))
+        .await;
+    assert!(resp.headers().get("etag").is_none());
+}
+
+#[tokio::test]
+async fn test_get_transactions_negotiates_content_type() {
+    let context = new_test_context(current_function_name!());
+    for (accept, content_type) in [
+        (None, mime_types::JSON),
+        (Some("application/json"), mime_types::JSON),
+        (Some(mime_types::BCS), mime_types::BCS),
+        (
+            Some("application/json, application/x.aptos.output+bcs;q=0.9"),
+            mime_types::BCS,
+        ),
+    ] {
+        for path in ["/transactions", "/transactions/0"] {
+            let mut req = warp::test::request().method("GET").path(path);
+            if let Some(accept) = accept {
+                req = req.header("accept", accept);
+            }
+            let resp = context.reply(req).await;
+            assert_eq!(resp.status(), 200);
+            assert_eq!(resp.headers()["content-type"], content_type);
+        }
+    }
+}
+
 fn gen_string(len: u64) -> String {
     let mut rng = thread_rng();
     std::iter::repeat(())
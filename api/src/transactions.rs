@@ -2,19 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accept_type::AcceptType,
+    accept_type::{accept_type, AcceptType},
     context::Context,
+    etag::with_etag,
     failpoint::fail_point,
-    metrics::metrics,
+    metrics::{metrics, metrics_by_accept_type},
     page::Page,
     param::{AddressParam, TransactionIdParam},
 };
 
 use aptos_api_types::{
-    mime_types::{BCS, BCS_SIGNED_TRANSACTION},
-    AsConverter, Error, LedgerInfo, Response, Transaction, TransactionData, TransactionId,
-    TransactionOnChainData, TransactionSigningMessage, UserCreateSigningMessageRequest,
-    UserTransactionRequest,
+    mime_types::BCS_SIGNED_TRANSACTION, AptosErrorCode, AsConverter, Error, LedgerInfo, Response,
+    Transaction, TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
+    UserCreateSigningMessageRequest, UserTransactionRequest,
 };
 use aptos_crypto::signing_message;
 use aptos_types::{
@@ -30,61 +30,44 @@ use aptos_types::transaction::{ExecutionStatus, TransactionInfo, TransactionStat
 use warp::{
     filters::BoxedFilter,
     http::{
-        header::{ACCEPT, CONTENT_TYPE},
+        header::{ACCEPT, CONTENT_TYPE, VARY},
         StatusCode,
     },
     reply, Filter, Rejection, Reply,
 };
 
 // GET /transactions/{txn-hash / version}
-pub fn get_json_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("transactions" / TransactionIdParam)
-        .and(warp::get())
-        .and(context.filter())
-        .map(|id, context| (id, context, AcceptType::Json))
-        .untuple_one()
-        .and_then(handle_get_transaction)
-        .with(metrics("get_json_transaction"))
-        .boxed()
-}
-
-// GET /transactions/{txn-hash / version}
-pub fn get_bcs_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("transactions" / TransactionIdParam)
-        .and(warp::get())
-        .and(warp::header::exact_ignore_case(ACCEPT.as_str(), BCS))
-        .and(context.filter())
-        .map(|id, context| (id, context, AcceptType::Bcs))
-        .untuple_one()
-        .and_then(handle_get_transaction)
-        .with(metrics("get_bcs_transaction"))
-        .boxed()
+// Replies with BCS if the Accept header asks for it, and JSON otherwise.
+pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
+    with_etag(
+        warp::path!("transactions" / TransactionIdParam)
+            .and(warp::get())
+            .and(context.filter())
+            .and(accept_type())
+            .and_then(handle_get_transaction),
+    )
+    // The body, and so the ETag, depends on the negotiated type
+    .map(|response| reply::with_header(response, VARY, ACCEPT.as_str()))
+    .with(metrics_by_accept_type(
+        "get_json_transaction",
+        "get_bcs_transaction",
+    ))
+    .boxed()
 }
 
 // GET /transactions?start={u64}&limit={u16}
-pub fn get_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+// Replies with BCS if the Accept header asks for it, and JSON otherwise.
+pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
         .and(context.filter())
-        .map(|page: Page, context: Context| (page, context, AcceptType::Json))
-        .untuple_one()
+        .and(accept_type())
         .and_then(handle_get_transactions)
-        .with(metrics("get_json_transactions"))
-        .boxed()
-}
-
-// GET /transactions?start={u64}&limit={u16}
-pub fn get_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
-    warp::path!("transactions")
-        .and(warp::get())
-        .and(warp::header::exact_ignore_case(ACCEPT.as_str(), BCS))
-        .and(warp::query::<Page>())
-        .and(context.filter())
-        .map(|page: Page, context: Context| (page, context, AcceptType::Bcs))
-        .untuple_one()
-        .and_then(handle_get_transactions)
-        .with(metrics("get_bcs_transactions"))
+        .with(metrics_by_accept_type(
+            "get_json_transactions",
+            "get_bcs_transactions",
+        ))
         .boxed()
 }
 
@@ -323,11 +306,12 @@ impl Transactions {
                 vm_status_opt
                     .map(|s| format!("{:?}", s))
                     .unwrap_or_else(|| "UNKNOWN".to_owned())
-            ))),
-            _ => Err(Error::bad_request(format!(
-                "transaction is rejected: {}",
-                mempool_status,
-            ))),
+            ))
+            .error_code(AptosErrorCode::InvalidTransaction)),
+            _ => Err(
+                Error::bad_request(format!("transaction is rejected: {}", mempool_status,))
+                    .error_code(AptosErrorCode::TransactionRejected),
+            ),
         }
     }
 
@@ -488,6 +472,7 @@ impl Transactions {
 
     fn transaction_not_found(&self, id: TransactionId) -> Error {
         Error::not_found("transaction", id, self.ledger_info.version())
+            .error_code(AptosErrorCode::TransactionNotFound)
     }
 
     fn get_by_version(&self, version: u64) -> Result<Option<TransactionData>> {
@@ -5,15 +5,61 @@ use serde::{Deserialize, Serialize};
 use std::{
     convert::From,
     fmt::{self, Display},
+    time::Duration,
 };
 use warp::{http::StatusCode, reject::Reject};
 
 use crate::U64;
 
+/// A stable, machine readable code for the kind of error, so that clients can
+/// branch on it rather than on the message. New codes may be added over time.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AptosErrorCode {
+    InvalidInput,
+    InvalidTransaction,
+    TransactionRejected,
+    NotFound,
+    AccountNotFound,
+    ResourceNotFound,
+    ModuleNotFound,
+    StateValueNotFound,
+    TableItemNotFound,
+    TransactionNotFound,
+    VersionNotFound,
+    VersionPruned,
+    CorsForbidden,
+    MethodNotAllowed,
+    ContentLengthRequired,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    ServiceUnavailable,
+    InternalError,
+}
+
+impl AptosErrorCode {
+    /// The code used for an error with the given status, unless a more
+    /// specific one is set.
+    pub fn from_status_code(code: StatusCode) -> Self {
+        match code {
+            StatusCode::BAD_REQUEST => AptosErrorCode::InvalidInput,
+            StatusCode::FORBIDDEN => AptosErrorCode::CorsForbidden,
+            StatusCode::NOT_FOUND => AptosErrorCode::NotFound,
+            StatusCode::METHOD_NOT_ALLOWED => AptosErrorCode::MethodNotAllowed,
+            StatusCode::LENGTH_REQUIRED => AptosErrorCode::ContentLengthRequired,
+            StatusCode::PAYLOAD_TOO_LARGE => AptosErrorCode::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => AptosErrorCode::UnsupportedMediaType,
+            StatusCode::SERVICE_UNAVAILABLE => AptosErrorCode::ServiceUnavailable,
+            _ => AptosErrorCode::InternalError,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Error {
     pub code: u16,
     pub message: String,
+    pub error_code: AptosErrorCode,
     /// Diem blockchain latest onchain ledger version.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aptos_ledger_version: Option<U64>,
@@ -24,6 +70,7 @@ impl Error {
         Self {
             code: code.as_u16(),
             message,
+            error_code: AptosErrorCode::from_status_code(code),
             aptos_ledger_version: None,
         }
     }
@@ -44,6 +91,18 @@ impl Error {
         .aptos_ledger_version(ledger_version)
     }
 
+    pub fn version_pruned(version: u64, oldest_ledger_version: u64, ledger_version: u64) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!(
+                "ledger version({}) has been pruned, the oldest available version is {}",
+                version, oldest_ledger_version
+            ),
+        )
+        .error_code(AptosErrorCode::VersionPruned)
+        .aptos_ledger_version(ledger_version)
+    }
+
     pub fn invalid_param<S: Display>(name: &str, value: S) -> Self {
         Self::bad_request(format!("invalid parameter {}: {}", name, value))
     }
@@ -64,6 +123,11 @@ impl Error {
         self.aptos_ledger_version = Some(ledger_version.into());
         self
     }
+
+    pub fn error_code(mut self, error_code: AptosErrorCode) -> Self {
+        self.error_code = error_code;
+        self
+    }
 }
 
 impl fmt::Display for Error {
@@ -78,6 +142,46 @@ impl fmt::Display for Error {
 
 impl Reject for Error {}
 
+/// A transient failure, e.g. the node being overloaded, where the request is
+/// expected to succeed if the client retries it later. It's replied to with a
+/// 503 and a Retry-After header, so well-behaved clients know to back off.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemporarilyUnavailable {
+    pub message: String,
+    pub retry_after: Duration,
+}
+
+impl TemporarilyUnavailable {
+    pub fn new<S: Display>(msg: S, retry_after: Duration) -> Self {
+        Self {
+            message: msg.to_string(),
+            retry_after,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    /// Retry-After only supports whole seconds, so round up to the next one.
+    pub fn retry_after_secs(&self) -> u64 {
+        let secs = self.retry_after.as_secs();
+        if self.retry_after.subsec_nanos() > 0 {
+            secs + 1
+        } else {
+            secs
+        }
+    }
+}
+
+impl fmt::Display for TemporarilyUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.status_code(), &self.message)
+    }
+}
+
+impl Reject for TemporarilyUnavailable {}
+
 impl From<anyhow::Error> for Error {
     fn from(e: anyhow::Error) -> Self {
         Self::internal(e)
@@ -92,7 +196,8 @@ impl From<serde_json::error::Error> for Error {
 
 #[cfg(test)]
 mod tests {
-    use crate::error::Error;
+    use crate::error::{AptosErrorCode, Error, TemporarilyUnavailable};
+    use std::time::Duration;
     use warp::http::StatusCode;
 
     #[test]
@@ -117,9 +222,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_error_code_serialization() {
+        let err = Error::bad_request("invalid address");
+        assert_eq!(err.error_code, AptosErrorCode::InvalidInput);
+        let err = Error::not_found("account", "address(0x1)", 1)
+            .error_code(AptosErrorCode::AccountNotFound);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 404,
+                "message": "account not found by address(0x1)",
+                "error_code": "account_not_found",
+                "aptos_ledger_version": "1",
+            })
+        );
+    }
+
     #[test]
     fn test_internal_error() {
         let err = Error::internal(anyhow::format_err!("hello"));
         assert_eq!(err.to_string(), "500 Internal Server Error: hello")
     }
+
+    #[test]
+    fn test_temporarily_unavailable() {
+        let err = TemporarilyUnavailable::new("overloaded", Duration::from_millis(1500));
+        assert_eq!(err.to_string(), "503 Service Unavailable: overloaded");
+        assert_eq!(err.retry_after_secs(), 2);
+        let err = TemporarilyUnavailable::new("overloaded", Duration::from_secs(3));
+        assert_eq!(err.retry_after_secs(), 3);
+    }
 }
@@ -19,7 +19,7 @@ pub use account::AccountData;
 pub use address::Address;
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_ascii_string, AsConverter, MoveConverter};
-pub use error::Error;
+pub use error::{AptosErrorCode, Error, TemporarilyUnavailable};
 pub use event_key::EventKey;
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
@@ -33,6 +33,15 @@ impl Response {
         })
     }
 
+    /// Returns `body` as is, for values that are already BCS serialized.
+    pub fn new_raw_bcs(ledger_info: LedgerInfo, body: Vec<u8>) -> Self {
+        Self {
+            ledger_info,
+            body,
+            is_bcs_response: true,
+        }
+    }
+
     pub fn new_bcs<T: Serialize>(ledger_info: LedgerInfo, body: &T) -> Result<Self, Error> {
         Ok(Self {
             ledger_info,
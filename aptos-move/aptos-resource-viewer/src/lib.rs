@@ -10,6 +10,7 @@ use aptos_vm::move_vm_ext::MoveResolverExt;
 use move_deps::{
     move_core_types::language_storage::StructTag, move_resource_viewer::MoveValueAnnotator,
 };
+use serde_json::{json, Map, Value};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
@@ -64,6 +65,48 @@ impl<'a, T: MoveResolverExt> AptosValueAnnotator<'a, T> {
     }
 }
 
+impl AnnotatedAccountStateBlob {
+    /// Render every resource as JSON, keyed by its type. See `annotated_struct_to_json`.
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.0
+                .iter()
+                .map(|(tag, value)| (tag.to_string(), annotated_struct_to_json(value)))
+                .collect(),
+        )
+    }
+}
+
+/// Render an annotated struct as `{"type": "<struct tag>", "fields": {...}}`. `u64` and `u128`
+/// values are rendered as strings since JSON numbers can't represent them exactly, and byte
+/// vectors as `0x` prefixed hex.
+pub fn annotated_struct_to_json(value: &AnnotatedMoveStruct) -> Value {
+    let fields: Map<String, Value> = value
+        .value
+        .iter()
+        .map(|(name, field)| (name.to_string(), annotated_value_to_json(field)))
+        .collect();
+    json!({
+        "type": value.type_.to_string(),
+        "fields": fields,
+    })
+}
+
+fn annotated_value_to_json(value: &AnnotatedMoveValue) -> Value {
+    match value {
+        AnnotatedMoveValue::U8(v) => json!(v),
+        AnnotatedMoveValue::U64(v) => json!(v.to_string()),
+        AnnotatedMoveValue::U128(v) => json!(v.to_string()),
+        AnnotatedMoveValue::Bool(v) => json!(v),
+        AnnotatedMoveValue::Address(v) => json!(v.to_hex_literal()),
+        AnnotatedMoveValue::Vector(_, vals) => {
+            Value::Array(vals.iter().map(annotated_value_to_json).collect())
+        }
+        AnnotatedMoveValue::Bytes(v) => json!(format!("0x{}", hex::encode(v))),
+        AnnotatedMoveValue::Struct(v) => annotated_struct_to_json(v),
+    }
+}
+
 impl Display for AnnotatedAccountStateBlob {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         writeln!(f, "{{")?;
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosValidatorInterface;
+use anyhow::Result;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_state::AccountState,
+    contract_event::EventWithVersion,
+    event::EventKey,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, TransactionOutput, Version},
+};
+use lru::LruCache;
+use std::sync::{Arc, Mutex};
+
+/// Wraps another `AptosValidatorInterface` and keeps the most recently read state values in
+/// memory. Historical state never changes, so entries never need to be invalidated; the cache is
+/// only bounded to `capacity` entries.
+pub struct CachedDebuggerInterface {
+    inner: Arc<dyn AptosValidatorInterface>,
+    state_values: Mutex<LruCache<(Version, StateKey), Option<StateValue>>>,
+}
+
+impl CachedDebuggerInterface {
+    pub fn new(inner: Arc<dyn AptosValidatorInterface>, capacity: usize) -> Self {
+        Self {
+            inner,
+            state_values: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+impl AptosValidatorInterface for CachedDebuggerInterface {
+    fn get_account_state_by_version(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountState>> {
+        self.inner.get_account_state_by_version(account, version)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        let key = (version, state_key.clone());
+        if let Some(state_value) = self.state_values.lock().unwrap().get(&key) {
+            return Ok(state_value.clone());
+        }
+        let state_value = self.inner.get_state_value_by_version(state_key, version)?;
+        self.state_values
+            .lock()
+            .unwrap()
+            .put(key, state_value.clone());
+        Ok(state_value)
+    }
+
+    fn get_events(
+        &self,
+        key: &EventKey,
+        start_seq: u64,
+        limit: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.inner.get_events(key, start_seq, limit)
+    }
+
+    fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
+        self.inner.get_committed_transactions(start, limit)
+    }
+
+    fn get_committed_transaction_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        self.inner.get_committed_transaction_outputs(start, limit)
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        self.inner.get_latest_version()
+    }
+
+    fn get_version_by_account_sequence(
+        &self,
+        account: AccountAddress,
+        seq: u64,
+    ) -> Result<Option<Version>> {
+        self.inner.get_version_by_account_sequence(account, seq)
+    }
+}
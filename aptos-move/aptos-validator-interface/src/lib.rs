@@ -1,9 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod cached_interface;
+mod rest_interface;
 mod storage_interface;
 
-pub use crate::storage_interface::DBDebuggerInterface;
+pub use crate::{
+    cached_interface::CachedDebuggerInterface, rest_interface::RestDebuggerInterface,
+    storage_interface::DBDebuggerInterface,
+};
 
 use anyhow::{anyhow, Result};
 use aptos_state_view::StateView;
@@ -16,13 +21,13 @@ use aptos_types::{
     event::EventKey,
     on_chain_config::ValidatorSet,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, Version},
+    transaction::{Transaction, TransactionOutput, Version},
 };
 use move_deps::move_binary_format::file_format::CompiledModule;
 
 // TODO(skedia) Clean up this interfact to remove account specific logic and move to state store
 // key-value interface with fine grained storage project
-pub trait AptosValidatorInterface: Sync {
+pub trait AptosValidatorInterface: Send + Sync {
     fn get_account_state_by_version(
         &self,
         account: AccountAddress,
@@ -44,6 +49,14 @@ pub trait AptosValidatorInterface: Sync {
 
     fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>>;
 
+    /// Get the outputs of the committed transactions in `[start, start + limit)`, as recorded on
+    /// chain.
+    fn get_committed_transaction_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>>;
+
     fn get_latest_version(&self) -> Result<Version>;
 
     fn get_version_by_account_sequence(
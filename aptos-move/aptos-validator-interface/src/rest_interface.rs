@@ -0,0 +1,266 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosValidatorInterface;
+use anyhow::{anyhow, bail, Result};
+use aptos_rest_client::{Client, TransactionOnChainData};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    account_state::AccountState,
+    contract_event::EventWithVersion,
+    event::EventKey,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, TransactionOutput, TransactionStatus, Version},
+};
+use move_deps::{
+    move_binary_format::{access::ModuleAccess, file_format::CompiledModule},
+    move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType},
+};
+use std::{collections::HashMap, future::Future};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// Maximum number of transactions or events the REST API returns in a single page.
+const MAX_PAGE_SIZE: u64 = 1000;
+
+/// An `AptosValidatorInterface` backed by the REST API of a fullnode.
+///
+/// Historical reads are only served as far back as the node keeps data; requests for older
+/// versions fail with an error naming the oldest version still available.
+pub struct RestDebuggerInterface {
+    client: Client,
+    // Only `None` while being dropped, see the `Drop` impl.
+    runtime: Option<Runtime>,
+}
+
+impl RestDebuggerInterface {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self {
+            client: Client::new(Url::parse(url)?),
+            runtime: Some(Runtime::new()?),
+        })
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime
+            .as_ref()
+            .expect("runtime is only taken when dropped")
+    }
+
+    /// Run `request` to completion. If it fails, check whether `version` has been pruned by the
+    /// node, in which case that is reported instead of the raw request error.
+    fn block_on<T, F>(&self, version: Version, request: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.runtime().block_on(request).map_err(|err| {
+            match self.ensure_version_available(version) {
+                Ok(()) => err,
+                Err(pruned) => pruned,
+            }
+        })
+    }
+
+    fn ensure_version_available(&self, version: Version) -> Result<()> {
+        let state = self
+            .runtime()
+            .block_on(self.client.get_ledger_information())?
+            .into_inner();
+        match state.oldest_ledger_version {
+            Some(oldest_version) if version < oldest_version => bail!(
+                "Version {} has been pruned by the REST endpoint, the oldest available version is {}",
+                version,
+                oldest_version
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetch the transactions in `[start, start + limit)` page by page. A range running past
+    /// the end of the ledger only returns the transactions up to the latest version.
+    fn get_committed_transaction_data(
+        &self,
+        mut start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        let latest_version = self.get_latest_version()?;
+        if start > latest_version {
+            return Ok(vec![]);
+        }
+        let mut limit = std::cmp::min(limit, latest_version - start + 1);
+        let mut result = vec![];
+        while limit != 0 {
+            let page_size = std::cmp::min(limit, MAX_PAGE_SIZE);
+            let mut page = self
+                .block_on(
+                    start,
+                    self.client
+                        .get_transactions_bcs(Some(start), Some(page_size)),
+                )?
+                .into_inner();
+            if page.is_empty() {
+                break;
+            }
+            start += page.len() as u64;
+            limit -= page.len() as u64;
+            result.append(&mut page);
+        }
+        Ok(result)
+    }
+}
+
+// Dropping a runtime blocks until its tasks are done, which panics when that happens inside an
+// async context, e.g. when the last handle to the debugger goes away in an async service.
+impl Drop for RestDebuggerInterface {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl AptosValidatorInterface for RestDebuggerInterface {
+    fn get_account_state_by_version(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountState>> {
+        let resources = match self.block_on(
+            version,
+            self.client
+                .get_account_resources_bcs_at_version(account, version),
+        ) {
+            Ok(resources) => resources.into_inner(),
+            Err(err) => {
+                let account_resource_key = StateKey::AccessPath(AccessPath::resource_access_path(
+                    ResourceKey::new(account, AccountResource::struct_tag()),
+                ));
+                return match self.get_state_value_by_version(&account_resource_key, version)? {
+                    None => Ok(None),
+                    Some(_) => Err(err),
+                };
+            }
+        };
+        let modules = self
+            .block_on(
+                version,
+                self.client.get_account_modules_at_version(account, version),
+            )?
+            .into_inner();
+
+        let mut key_value_map = HashMap::new();
+        for (struct_tag, bytes) in resources {
+            key_value_map.insert(
+                StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+                    account, struct_tag,
+                ))),
+                StateValue::from(bytes),
+            );
+        }
+        for module in modules {
+            let bytes: Vec<u8> = module.bytecode.into();
+            let module_id = CompiledModule::deserialize(&bytes)
+                .map_err(|e| anyhow!("Failure deserializing module: {:?}", e))?
+                .self_id();
+            key_value_map.insert(
+                StateKey::AccessPath(AccessPath::code_access_path(module_id)),
+                StateValue::from(bytes),
+            );
+        }
+        AccountState::from_access_paths_and_values(&key_value_map)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        Ok(self
+            .block_on(
+                version,
+                self.client
+                    .get_raw_state_value_at_version(state_key, version),
+            )?
+            .map(StateValue::from))
+    }
+
+    fn get_events(
+        &self,
+        key: &EventKey,
+        mut start_seq: u64,
+        mut limit: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        let mut result = vec![];
+        while limit != 0 {
+            let page_size = std::cmp::min(limit, MAX_PAGE_SIZE);
+            let mut page = self
+                .runtime()
+                .block_on(
+                    self.client
+                        .get_events_bcs(key, Some(start_seq), Some(page_size)),
+                )?
+                .into_inner();
+            let is_last_page = (page.len() as u64) < page_size;
+            start_seq += page.len() as u64;
+            limit -= page.len() as u64;
+            result.append(&mut page);
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
+        Ok(self
+            .get_committed_transaction_data(start, limit)?
+            .into_iter()
+            .map(|txn| txn.transaction)
+            .collect())
+    }
+
+    fn get_committed_transaction_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        Ok(self
+            .get_committed_transaction_data(start, limit)?
+            .into_iter()
+            .map(|txn| {
+                TransactionOutput::new(
+                    txn.changes,
+                    txn.events,
+                    txn.info.gas_used(),
+                    TransactionStatus::Keep(txn.info.status().clone()),
+                )
+            })
+            .collect())
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(self
+            .runtime()
+            .block_on(self.client.get_ledger_information())?
+            .into_inner()
+            .version)
+    }
+
+    fn get_version_by_account_sequence(
+        &self,
+        account: AccountAddress,
+        seq: u64,
+    ) -> Result<Option<Version>> {
+        Ok(self
+            .runtime()
+            .block_on(
+                self.client
+                    .get_account_transactions(account, Some(seq), Some(1)),
+            )?
+            .into_inner()
+            .first()
+            .and_then(|txn| txn.version()))
+    }
+}
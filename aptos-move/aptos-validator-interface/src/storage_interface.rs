@@ -10,7 +10,7 @@ use aptos_types::{
     contract_event::EventWithVersion,
     event::EventKey,
     state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
-    transaction::{Transaction, Version},
+    transaction::{Transaction, TransactionOutput, Version},
 };
 use aptosdb::AptosDB;
 use std::{path::Path, sync::Arc};
@@ -68,6 +68,20 @@ impl AptosValidatorInterface for DBDebuggerInterface {
             .transactions)
     }
 
+    fn get_committed_transaction_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        Ok(self
+            .0
+            .get_transaction_outputs(start, limit, self.get_latest_version()?)?
+            .transactions_and_outputs
+            .into_iter()
+            .map(|(_, output)| output)
+            .collect())
+    }
+
     fn get_latest_version(&self) -> Result<Version> {
         let (version, _) = self
             .0
@@ -3,7 +3,7 @@
 
 use anyhow::{anyhow, bail, format_err, Result};
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AnnotatedMoveStruct, AptosValueAnnotator};
-use aptos_state_view::StateView;
+use aptos_state_view::{StateView, StateViewId};
 use aptos_types::{
     access_path,
     access_path::AccessPath,
@@ -13,10 +13,21 @@ use aptos_types::{
     account_view::AccountView,
     contract_event::{ContractEvent, EventWithVersion},
     event::EventKey,
-    transaction::{ChangeSet, Transaction, TransactionOutput, Version, WriteSetPayload},
+    on_chain_config::{
+        access_path_for_config, check_config_bytes, contains_new_epoch_event, ConfigID,
+        OnChainConfig, VMConfig, ValidatorSet, Version as VersionConfig,
+    },
+    state_store::state_key::StateKey,
+    transaction::{
+        ChangeSet, Transaction, TransactionOutput, TransactionPayload, TransactionStatus, Version,
+        WriteSetPayload,
+    },
     write_set::WriteOp,
 };
-use aptos_validator_interface::{AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView};
+use aptos_validator_interface::{
+    AptosValidatorInterface, CachedDebuggerInterface, DBDebuggerInterface, DebuggerStateView,
+    RestDebuggerInterface,
+};
 use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
     logging::AdapterLogSchema,
@@ -24,37 +35,605 @@ use aptos_vm::{
     AptosVM, VMExecutor,
 };
 use move_deps::{
-    move_binary_format::{errors::VMResult, file_format::CompiledModule},
+    move_binary_format::{
+        access::ModuleAccess, binary_views::BinaryIndexedView, compatibility::Compatibility,
+        errors::VMResult, file_format::CompiledModule, normalized,
+    },
+    move_bytecode_source_map::mapping::SourceMapping,
     move_cli,
     move_cli::sandbox::utils::on_disk_state_view::OnDiskStateView,
     move_command_line_common::env::get_bytecode_version_from_env,
     move_compiler,
     move_compiler::{compiled_unit::AnnotatedCompiledUnit, Compiler, Flags},
-    move_core_types::{effects::ChangeSet as MoveChanges, language_storage::TypeTag},
+    move_core_types::{
+        effects::ChangeSet as MoveChanges,
+        gas_schedule::{AbstractMemorySize, CostTable, GasAlgebra, GasUnits},
+        language_storage::{ModuleId, StructTag, TypeTag},
+        vm_status::VMStatus,
+    },
+    move_disassembler::disassembler::{Disassembler, DisassemblerOptions},
+    move_ir_types::location::Spanned,
     move_vm_runtime::session::{SerializedReturnValues, Session},
     move_vm_test_utils::DeltaStorage,
-    move_vm_types::gas_schedule::GasStatus,
+    move_vm_types::gas_schedule::{calculate_intrinsic_gas, GasStatus},
 };
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     convert::TryFrom,
+    fs,
+    io::Write,
+    ops::Range,
     path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
 };
+use thiserror::Error;
+use tokio::task::spawn_blocking;
 
 #[cfg(test)]
 mod unit_tests;
 
+/// Maximum number of transactions fetched and executed at once by `AptosDebugger::replay_iter`.
+const REPLAY_ITER_BATCH_SIZE: u64 = 1000;
+
+/// Name of the JSON index written next to the saved state when manifests are enabled.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Name of the JSON index of saved events, grouped by type, written next to the saved state when
+/// the events index is enabled.
+pub const EVENTS_INDEX_FILE_NAME: &str = "events_index.json";
+
+/// The result of `AptosDebugger::execute_transactions_by_epoch`.
+#[derive(Debug)]
+pub struct EpochExecutionResult {
+    /// Outputs of the transactions up to and including the first reconfiguration.
+    pub outputs: Vec<TransactionOutput>,
+    /// Versions of the transactions that were executed but dropped because they follow a
+    /// reconfiguration. Empty if the whole batch belongs to the same epoch.
+    pub skipped_versions: Range<Version>,
+    /// Version of the transaction that reconfigured, i.e. the last one in `outputs`, if any. The
+    /// configs of the new epoch can be read right after it.
+    pub reconfig_at: Option<Version>,
+}
+
+impl EpochExecutionResult {
+    pub fn num_skipped(&self) -> u64 {
+        self.skipped_versions.end - self.skipped_versions.start
+    }
+}
+
+/// Errors specific to replay, as opposed to failures of the underlying storage or VM.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Transaction at version {version} didn't finish executing within {timeout:?}")]
+    TimedOut { version: Version, timeout: Duration },
+    #[error("Override of module {module_id} changes the layout of structs stored on chain")]
+    IncompatibleModuleOverride { module_id: ModuleId },
+}
+
+/// The state of a block after one of its transactions, see
+/// `AptosDebugger::execute_block_with_state_snapshots`.
+#[derive(Debug)]
+pub struct BlockStep {
+    pub output: TransactionOutput,
+    /// The value of every key written by this or an earlier transaction of the block, `None` if
+    /// the key was deleted. Keys not in here still have the value they had before the block.
+    pub state_delta: HashMap<StateKey, Option<Vec<u8>>>,
+}
+
+/// A human readable description of a resource saved to the on-disk state view.
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct ManifestEntry {
+    pub address: String,
+    /// The `module::name` path of the resource type.
+    pub path: String,
+    pub type_params: Vec<String>,
+}
+
+impl ManifestEntry {
+    fn new(address: AccountAddress, tag: &StructTag) -> Self {
+        Self {
+            address: address.to_hex_literal(),
+            path: format!("{}::{}", tag.module, tag.name),
+            type_params: tag.type_params.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+/// Progress of `AptosDebugger::execute_past_transactions` saved to the checkpoint file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReplayCheckpoint {
+    /// Version of this layout, bumped on incompatible changes so an old file is rejected rather
+    /// than misread. See `ReplayCheckpoint::FORMAT_VERSION`.
+    pub format_version: u32,
+    /// The range being replayed, `[begin, begin + limit)`.
+    pub begin: Version,
+    pub limit: u64,
+    /// Number of transactions from `begin` on whose outputs have been fully replayed.
+    pub num_transactions: u64,
+    pub total_gas_used: u64,
+}
+
+impl ReplayCheckpoint {
+    pub const FORMAT_VERSION: u32 = 1;
+
+    fn new(begin: Version, limit: u64) -> Self {
+        Self {
+            format_version: Self::FORMAT_VERSION,
+            begin,
+            limit,
+            num_transactions: 0,
+            total_gas_used: 0,
+        }
+    }
+
+    /// The last version that has been fully replayed, if any.
+    pub fn last_version(&self) -> Option<Version> {
+        if self.num_transactions == 0 {
+            None
+        } else {
+            Some(self.begin + self.num_transactions - 1)
+        }
+    }
+
+    /// Load the checkpoint for replaying `[begin, begin + limit)` from `path`, if any.
+    pub fn load(path: &Path, begin: Version, limit: u64) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let checkpoint: Self = serde_json::from_slice(&fs::read(path)?)?;
+        if checkpoint.format_version != Self::FORMAT_VERSION {
+            bail!(
+                "Checkpoint {} has format version {}, expected {}",
+                path.display(),
+                checkpoint.format_version,
+                Self::FORMAT_VERSION
+            );
+        }
+        if checkpoint.begin != begin || checkpoint.limit != limit {
+            bail!(
+                "Checkpoint {} is for versions [{}, {}), remove it to replay [{}, {})",
+                path.display(),
+                checkpoint.begin,
+                checkpoint.begin + checkpoint.limit,
+                begin,
+                begin + limit
+            );
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Write the checkpoint to `path`. The file is replaced atomically, so a crash never leaves
+    /// a partially written checkpoint behind.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn record(&mut self, outputs: &[TransactionOutput]) {
+        self.num_transactions += outputs.len() as u64;
+        self.total_gas_used += outputs.iter().map(|output| output.gas_used()).sum::<u64>();
+    }
+}
+
+/// An event saved to the on-disk state view, as listed in the events index.
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct EventIndexEntry {
+    /// The hex encoded event key.
+    pub key: String,
+    pub sequence_number: u64,
+}
+
+/// Decentralization metrics computed over the voting power of a validator set.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VotingPowerDistribution {
+    pub num_validators: usize,
+    pub total_voting_power: u128,
+    /// Minimum number of validators whose combined voting power exceeds 1/3 of the total, i.e.
+    /// enough to halt consensus.
+    pub nakamoto_coefficient: usize,
+    /// Share of the total voting power held by the top k validators, for k in `TOP_K_SHARES`.
+    pub top_k_shares: BTreeMap<usize, f64>,
+    /// Gini coefficient of the voting power, from 0 (equal) to 1 (fully concentrated).
+    pub gini: f64,
+}
+
+impl VotingPowerDistribution {
+    pub const TOP_K_SHARES: &'static [usize] = &[1, 5, 10];
+
+    pub fn from_voting_powers(mut voting_powers: Vec<u64>) -> Self {
+        voting_powers.sort_unstable_by(|a, b| b.cmp(a));
+        let num_validators = voting_powers.len();
+        let total_voting_power: u128 = voting_powers.iter().map(|p| *p as u128).sum();
+
+        let mut nakamoto_coefficient = 0;
+        let mut accumulated = 0u128;
+        for power in &voting_powers {
+            if total_voting_power == 0 || accumulated * 3 > total_voting_power {
+                break;
+            }
+            accumulated += *power as u128;
+            nakamoto_coefficient += 1;
+        }
+
+        let share = |power: u128| {
+            if total_voting_power == 0 {
+                0.0
+            } else {
+                power as f64 / total_voting_power as f64
+            }
+        };
+        let top_k_shares = Self::TOP_K_SHARES
+            .iter()
+            .map(|k| {
+                let top: u128 = voting_powers.iter().take(*k).map(|p| *p as u128).sum();
+                (*k, share(top))
+            })
+            .collect();
+
+        // With powers sorted ascending and 1-indexed:
+        //   G = 2 * sum(i * x_i) / (n * sum(x)) - (n + 1) / n
+        let gini = if num_validators == 0 || total_voting_power == 0 {
+            0.0
+        } else {
+            let n = num_validators as f64;
+            let weighted_sum: f64 = voting_powers
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, power)| (i + 1) as f64 * *power as f64)
+                .sum();
+            2.0 * weighted_sum / (n * total_voting_power as f64) - (n + 1.0) / n
+        };
+
+        Self {
+            num_validators,
+            total_voting_power,
+            nakamoto_coefficient,
+            top_k_shares,
+            gini,
+        }
+    }
+}
+
+/// Gas consumed by a transaction on chain versus when replayed, see
+/// `AptosDebugger::compare_gas_usage`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GasComparison {
+    pub version: Version,
+    pub committed_gas: u64,
+    pub recomputed_gas: u64,
+    /// `recomputed_gas - committed_gas`
+    pub delta: i128,
+}
+
+/// The part of a `TransactionOutput` that differs between replay and chain.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DivergentField {
+    WriteSet,
+    Events,
+    GasUsed,
+    Status,
+}
+
+/// A difference between the replayed and the committed output of a transaction, see
+/// `AptosDebugger::verify_past_transactions`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReplayDivergence {
+    pub version: Version,
+    pub field: DivergentField,
+    pub summary: String,
+}
+
+/// Compare the `replayed` output of the transaction at `version` against the `committed` one.
+pub fn diff_transaction_outputs(
+    version: Version,
+    replayed: &TransactionOutput,
+    committed: &TransactionOutput,
+) -> Vec<ReplayDivergence> {
+    let mut divergences = vec![];
+    let mut diverge = |field, summary| {
+        divergences.push(ReplayDivergence {
+            version,
+            field,
+            summary,
+        })
+    };
+    if replayed.write_set() != committed.write_set() {
+        let replayed_writes: BTreeMap<_, _> =
+            replayed.write_set().iter().map(|(k, v)| (k, v)).collect();
+        let committed_writes: BTreeMap<_, _> =
+            committed.write_set().iter().map(|(k, v)| (k, v)).collect();
+        let only_replayed = replayed_writes
+            .keys()
+            .filter(|k| !committed_writes.contains_key(*k))
+            .count();
+        let only_committed = committed_writes
+            .keys()
+            .filter(|k| !replayed_writes.contains_key(*k))
+            .count();
+        let changed = replayed_writes
+            .iter()
+            .filter(|(k, v)| matches!(committed_writes.get(*k), Some(op) if op != *v))
+            .count();
+        diverge(
+            DivergentField::WriteSet,
+            format!(
+                "{} keys only written by replay, {} only written on chain, {} written differently",
+                only_replayed, only_committed, changed
+            ),
+        );
+    }
+    if replayed.events() != committed.events() {
+        diverge(
+            DivergentField::Events,
+            format!(
+                "replay emitted {} events, chain recorded {}",
+                replayed.events().len(),
+                committed.events().len()
+            ),
+        );
+    }
+    if replayed.gas_used() != committed.gas_used() {
+        diverge(
+            DivergentField::GasUsed,
+            format!(
+                "replay used {} gas, chain recorded {}",
+                replayed.gas_used(),
+                committed.gas_used()
+            ),
+        );
+    }
+    if replayed.status() != committed.status() {
+        diverge(
+            DivergentField::Status,
+            format!(
+                "replay status {:?}, chain recorded {:?}",
+                replayed.status(),
+                committed.status()
+            ),
+        );
+    }
+    divergences
+}
+
+/// One line written by `AptosDebugger::execute_past_transactions_to_writer`. Byte strings are
+/// hex encoded without a `0x` prefix.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReplayOutputRecord {
+    pub version: Version,
+    pub gas_used: u64,
+    /// Serialized through its serde representation, e.g. `{"Keep":"Success"}`.
+    pub status: TransactionStatus,
+    pub write_set: Vec<WriteRecord>,
+    pub events: Vec<EventRecord>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct WriteRecord {
+    /// The BCS encoded `StateKey`, as accepted by the `/state/raw` REST endpoint.
+    pub state_key: String,
+    /// The new value, or `None` if the key is deleted.
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventRecord {
+    pub key: String,
+    pub sequence_number: u64,
+    pub type_tag: String,
+    pub data: String,
+}
+
+impl ReplayOutputRecord {
+    pub fn new(version: Version, output: &TransactionOutput) -> Result<Self> {
+        let mut write_set = vec![];
+        for (state_key, op) in output.write_set() {
+            write_set.push(WriteRecord {
+                state_key: hex::encode(bcs::to_bytes(state_key)?),
+                value: match op {
+                    WriteOp::Value(value) => Some(hex::encode(value)),
+                    WriteOp::Deletion => None,
+                },
+            });
+        }
+        let events = output
+            .events()
+            .iter()
+            .map(|event| EventRecord {
+                key: hex::encode(event.key().as_bytes()),
+                sequence_number: event.sequence_number(),
+                type_tag: event.type_tag().to_string(),
+                data: hex::encode(event.event_data()),
+            })
+            .collect();
+        Ok(Self {
+            version,
+            gas_used: output.gas_used(),
+            status: output.status().clone(),
+            write_set,
+            events,
+        })
+    }
+}
+
+/// Whether a resource or module appeared, disappeared or changed between two versions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A resource or module that differs between two versions of an account, see
+/// `AptosDebugger::diff_account_state`. `None` means the item doesn't exist at that version.
+#[derive(Debug)]
+pub enum ResourceDiff {
+    Resource {
+        tag: StructTag,
+        before: Option<AnnotatedMoveStruct>,
+        after: Option<AnnotatedMoveStruct>,
+    },
+    Module {
+        id: ModuleId,
+        before: Option<Vec<u8>>,
+        after: Option<Vec<u8>>,
+    },
+}
+
+impl ResourceDiff {
+    pub fn kind(&self) -> DiffKind {
+        let (exists_before, exists_after) = match self {
+            Self::Resource { before, after, .. } => (before.is_some(), after.is_some()),
+            Self::Module { before, after, .. } => (before.is_some(), after.is_some()),
+        };
+        match (exists_before, exists_after) {
+            (false, _) => DiffKind::Added,
+            (true, false) => DiffKind::Removed,
+            (true, true) => DiffKind::Modified,
+        }
+    }
+}
+
+/// A resource written by a session, see `AptosDebugger::run_session_at_version_annotated`.
+#[derive(Debug)]
+pub enum AnnotatedWrite {
+    Value(AnnotatedMoveStruct),
+    Deletion,
+}
+
+/// Which end of the transition from passing to failing a bisection looks for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BisectDirection {
+    /// The first version where the predicate fails.
+    FirstFailing,
+    /// The last version where the predicate still holds.
+    LastPassing,
+}
+
+/// Gas charges keyed by a stack of labels, outermost first, serializable in the folded-stack
+/// text format. The stacks recorded by `AptosDebugger::profile_transaction_at_version` are not
+/// Move call frames, see there.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(from = "Vec<GasProfileEntry>", into = "Vec<GasProfileEntry>")]
+pub struct GasProfile {
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+/// The gas charged to one call stack of a `GasProfile`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GasProfileEntry {
+    pub stack: Vec<String>,
+    pub gas: u64,
+}
+
+impl From<Vec<GasProfileEntry>> for GasProfile {
+    fn from(entries: Vec<GasProfileEntry>) -> Self {
+        let mut profile = Self::new();
+        for entry in entries {
+            profile.record(entry.stack, entry.gas);
+        }
+        profile
+    }
+}
+
+impl From<GasProfile> for Vec<GasProfileEntry> {
+    fn from(profile: GasProfile) -> Self {
+        profile.entries().collect()
+    }
+}
+
+impl GasProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge `gas` to `stack`, outermost frame first. Charges to the same stack accumulate.
+    pub fn record(&mut self, stack: Vec<String>, gas: u64) {
+        *self.stacks.entry(stack).or_insert(0) += gas;
+    }
+
+    pub fn total_gas(&self) -> u64 {
+        self.stacks.values().sum()
+    }
+
+    /// The recorded stacks in lexicographic order.
+    pub fn entries(&self) -> impl Iterator<Item = GasProfileEntry> + '_ {
+        self.stacks.iter().map(|(stack, gas)| GasProfileEntry {
+            stack: stack.clone(),
+            gas: *gas,
+        })
+    }
+
+    /// Write one `frame1;frame2;... gas` line per recorded stack.
+    pub fn write_folded<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for (stack, gas) in &self.stacks {
+            let frames: Vec<String> = stack
+                .iter()
+                .map(|frame| frame.replace(';', ":").replace(' ', "_"))
+                .collect();
+            writeln!(writer, "{} {}", frames.join(";"), gas)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct AptosDebugger {
-    debugger: Box<dyn AptosValidatorInterface>,
+    debugger: Arc<dyn AptosValidatorInterface>,
     build_dir: PathBuf,
     storage_dir: PathBuf,
+    save_manifest: bool,
+    save_events_index: bool,
+    checkpoint_path: Option<PathBuf>,
 }
 
 impl AptosDebugger {
     pub fn new(debugger: Box<dyn AptosValidatorInterface>) -> Self {
         Self {
-            debugger,
+            debugger: debugger.into(),
             build_dir: PathBuf::from(move_cli::DEFAULT_BUILD_DIR),
             storage_dir: PathBuf::from(move_cli::DEFAULT_STORAGE_DIR),
+            save_manifest: false,
+            save_events_index: false,
+            checkpoint_path: None,
+        }
+    }
+
+    /// When saving write sets or account states, also maintain a JSON index of the saved
+    /// resources (see `MANIFEST_FILE_NAME`) so the on-disk files can be mapped back to types.
+    pub fn with_manifest(mut self, save_manifest: bool) -> Self {
+        self.save_manifest = save_manifest;
+        self
+    }
+
+    /// When saving write sets, also maintain a JSON index of the saved events grouped by type (see
+    /// `EVENTS_INDEX_FILE_NAME`). The event data itself is still only saved as BCS.
+    pub fn with_events_index(mut self, save_events_index: bool) -> Self {
+        self.save_events_index = save_events_index;
+        self
+    }
+
+    /// Record the progress of `execute_past_transactions` in `checkpoint_path` after each epoch,
+    /// and resume from it when replaying the same range again, e.g. after a crash.
+    pub fn with_checkpoint(mut self, checkpoint_path: PathBuf) -> Self {
+        self.checkpoint_path = Some(checkpoint_path);
+        self
+    }
+
+    /// Keep up to `capacity` historical state values in memory, so that repeated reads of the
+    /// same keys, e.g. when bisecting, don't go back to the DB or REST endpoint.
+    pub fn with_state_cache(self, capacity: usize) -> Self {
+        Self {
+            debugger: Arc::new(CachedDebuggerInterface::new(self.debugger, capacity)),
+            ..self
         }
     }
 
@@ -64,48 +643,712 @@ impl AptosDebugger {
         )?)))
     }
 
-    pub fn execute_transactions_at_version(
+    /// Read the chain through the REST API of the fullnode at `url` instead of a local DB.
+    pub fn rest(url: &str) -> Result<Self> {
+        Ok(Self::new(Box::new(RestDebuggerInterface::new(url)?)))
+    }
+
+    /// Execute `txns` as a block on top of the state right before `version`.
+    ///
+    /// With a `per_txn_timeout`, each transaction instead runs alone on a worker thread, against
+    /// the committed state right before its own version, and a transaction that exceeds the
+    /// budget fails the call with `ReplayError::TimedOut`. For committed history this is
+    /// equivalent to executing the block. The worker of a timed out transaction can't be
+    /// interrupted and keeps running in the background until the VM returns.
+    pub fn execute_transactions_at_version(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        per_txn_timeout: Option<Duration>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let timeout = match per_txn_timeout {
+            Some(timeout) => timeout,
+            None => {
+                let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+                return AptosVM::execute_block(txns, &state_view)
+                    .map_err(|err| format_err!("Unexpected VM Error: {:?}", err));
+            }
+        };
+        txns.into_iter()
+            .enumerate()
+            .map(|(idx, txn)| {
+                self.execute_transaction_with_timeout(version + idx as u64, txn, timeout)
+            })
+            .collect()
+    }
+
+    fn execute_transaction_with_timeout(
+        &self,
+        version: Version,
+        txn: Transaction,
+        timeout: Duration,
+    ) -> Result<TransactionOutput> {
+        let debugger = self.debugger.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name(format!("replay-{}", version))
+            .spawn(move || {
+                let state_view = DebuggerStateView::new(&*debugger, version.checked_sub(1));
+                // The receiver is gone if the transaction already timed out.
+                let _ = sender.send(AptosVM::execute_block(vec![txn], &state_view));
+            })?;
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result
+                .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+                .pop()
+                .ok_or_else(|| anyhow!("No output produced at version {}", version)),
+            Err(RecvTimeoutError::Timeout) => {
+                Err(ReplayError::TimedOut { version, timeout }.into())
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                bail!(
+                    "Execution of the transaction at version {} panicked",
+                    version
+                )
+            }
+        }
+    }
+
+    /// Same as `execute_transactions_at_version`, but also returns the `VMStatus` of each
+    /// transaction, which carries the abort location and sub-status that the output drops.
+    pub fn execute_transactions_at_version_with_status(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+    ) -> Result<Vec<(TransactionOutput, VMStatus)>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        Ok(AptosVM::execute_block_and_keep_vm_status(txns, &state_view)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+            .into_iter()
+            .map(|(status, output)| (output, status))
+            .collect())
+    }
+
+    /// Execute `txns` at `version` twice and check that both runs produce byte-identical outputs.
+    /// A mismatch means the VM is non-deterministic, e.g. because it depends on hash map
+    /// iteration order or on the wall clock.
+    pub fn check_execution_determinism(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+    ) -> Result<()> {
+        let first = self.execute_transactions_at_version(version, txns.clone(), None)?;
+        let second = self.execute_transactions_at_version(version, txns, None)?;
+        if first.len() != second.len() {
+            bail!(
+                "Non-deterministic execution at version {}: {} outputs vs {} outputs",
+                version,
+                first.len(),
+                second.len()
+            );
+        }
+        for (idx, (lhs, rhs)) in first.iter().zip(second.iter()).enumerate() {
+            if bcs::to_bytes(lhs)? != bcs::to_bytes(rhs)? {
+                bail!(
+                    "Non-deterministic execution at version {}:\nfirst run: {:?}\nsecond run: {:?}",
+                    version + idx as u64,
+                    lhs,
+                    rhs
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as `execute_transactions_at_version`, but the values in `overrides` shadow the
+    /// historical state. A `None` value makes the key appear deleted.
+    fn execute_transactions_with_overrides(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        overrides: &HashMap<StateKey, Option<Vec<u8>>>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        let override_view = OverrideStateView::new(&state_view, overrides);
+        AptosVM::execute_block(txns, &override_view)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
+    }
+
+    /// Execute the block `txns` starting at `version` one transaction at a time, and return for
+    /// each transaction its output together with the state delta accumulated so far, so the state
+    /// in between two transactions can be inspected with `get_state_value_in_block`.
+    ///
+    /// Execution stops after the first reconfiguration, the remaining transactions would need to
+    /// run in the next epoch.
+    pub fn execute_block_with_state_snapshots(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+    ) -> Result<Vec<BlockStep>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        let mut state_delta = HashMap::new();
+        let mut steps = vec![];
+        for txn in txns {
+            let output = AptosVM::execute_block(
+                vec![txn],
+                &OverrideStateView::new(&state_view, &state_delta),
+            )
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+            .pop()
+            .ok_or_else(|| {
+                anyhow!(
+                    "No output produced at version {}",
+                    version + steps.len() as u64
+                )
+            })?;
+            for (key, op) in output.write_set() {
+                let value = match op {
+                    WriteOp::Value(bytes) => Some(bytes.clone()),
+                    WriteOp::Deletion => None,
+                };
+                state_delta.insert(key.clone(), value);
+            }
+            let reconfiguration = is_reconfiguration(&output);
+            steps.push(BlockStep {
+                output,
+                state_delta: state_delta.clone(),
+            });
+            if reconfiguration {
+                break;
+            }
+        }
+        Ok(steps)
+    }
+
+    /// Read `state_key` as of `step` of the block that started at `version`.
+    pub fn get_state_value_in_block(
+        &self,
+        version: Version,
+        step: &BlockStep,
+        state_key: &StateKey,
+    ) -> Result<Option<Vec<u8>>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        OverrideStateView::new(&state_view, &step.state_delta).get_state_value(state_key)
+    }
+
+    /// Execute `txns` at `version` with the on-chain configs in `overrides` replaced by the given
+    /// serialized values, e.g. to see how a block would have behaved under another gas schedule.
+    ///
+    /// Overriding the `ValidatorSet` or `Version` doesn't emit a reconfiguration by itself, but a
+    /// transaction in the block that reconfigures would then start an epoch that never existed on
+    /// chain. That is reported as an error rather than silently skipping the rest of the block.
+    pub fn execute_transactions_at_version_with_config_override(
+        &self,
+        version: Version,
+        txns: Vec<Transaction>,
+        overrides: HashMap<ConfigID, Vec<u8>>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let mut state_overrides = HashMap::new();
+        for (config_id, bytes) in &overrides {
+            check_config_bytes(*config_id, bytes)?;
+            state_overrides.insert(
+                StateKey::AccessPath(access_path_for_config(*config_id)),
+                Some(bytes.clone()),
+            );
+        }
+        let num_txns = txns.len();
+        let outputs = self.execute_transactions_with_overrides(version, txns, &state_overrides)?;
+        let overrides_epoch_config = overrides.contains_key(&ValidatorSet::CONFIG_ID)
+            || overrides.contains_key(&VersionConfig::CONFIG_ID);
+        if overrides_epoch_config {
+            if let Some(idx) = outputs.iter().position(is_reconfiguration) {
+                if idx + 1 < num_txns {
+                    bail!(
+                        "Transaction at version {} reconfigures with an overridden ValidatorSet or \
+                         Version, the {} transactions after it can't be replayed",
+                        version + idx as u64,
+                        num_txns - idx - 1
+                    );
+                }
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Execute `txn` at `version` with the on-chain code of each module in `modules` replaced by
+    /// the given bytes, e.g. to try a locally patched framework module against a historical
+    /// transaction.
+    ///
+    /// The override only lives in the state view used for this execution, nothing is written
+    /// back. Resources keep their on-chain bytes and the VM decodes them with the struct layouts
+    /// of the overriding modules, which would silently reinterpret the data if a layout changed.
+    /// Overrides whose struct layouts aren't compatible with the module on chain are therefore
+    /// rejected with `ReplayError::IncompatibleModuleOverride`.
+    pub fn execute_transaction_at_version_with_module_override(
+        &self,
+        version: Version,
+        txn: Transaction,
+        modules: Vec<(ModuleId, Vec<u8>)>,
+    ) -> Result<TransactionOutput> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        let mut overrides = HashMap::new();
+        for (module_id, bytes) in modules {
+            let module = CompiledModule::deserialize(&bytes)
+                .map_err(|e| anyhow!("Failure deserializing module {}: {:?}", module_id, e))?;
+            if module.self_id() != module_id {
+                bail!(
+                    "Module bytes for {} define module {} instead",
+                    module_id,
+                    module.self_id()
+                );
+            }
+            let state_key = StateKey::AccessPath(AccessPath::code_access_path(module_id.clone()));
+            if let Some(on_chain_bytes) = state_view.get_state_value(&state_key)? {
+                let on_chain_module =
+                    CompiledModule::deserialize(&on_chain_bytes).map_err(|e| {
+                        anyhow!(
+                            "Failure deserializing on-chain module {}: {:?}",
+                            module_id,
+                            e
+                        )
+                    })?;
+                let compatibility = Compatibility::check(
+                    &normalized::Module::new(&on_chain_module),
+                    &normalized::Module::new(&module),
+                );
+                if !compatibility.struct_layout {
+                    return Err(ReplayError::IncompatibleModuleOverride { module_id }.into());
+                }
+            }
+            overrides.insert(state_key, Some(bytes));
+        }
+        self.execute_transactions_with_overrides(version, vec![txn], &overrides)?
+            .pop()
+            .ok_or_else(|| anyhow!("No output produced at version {}", version))
+    }
+
+    /// Replay `limit` transactions starting at `begin` and compare the gas each of them used on
+    /// chain against the gas used when replayed. If `gas_schedule` is provided, it replaces the
+    /// on-chain gas schedule during the replay, which shows how a schedule change would shift
+    /// costs; otherwise any difference indicates a divergence in the gas metering itself. A range
+    /// running past the end of the ledger stops at the latest committed transaction.
+    pub fn compare_gas_usage(
+        &self,
+        mut begin: Version,
+        limit: u64,
+        gas_schedule: Option<CostTable>,
+    ) -> Result<Vec<GasComparison>> {
+        let mut overrides = HashMap::new();
+        if let Some(gas_schedule) = gas_schedule {
+            overrides.insert(
+                StateKey::AccessPath(access_path_for_config(VMConfig::CONFIG_ID)),
+                Some(VMConfig { gas_schedule }.serialize_into_bytes()?),
+            );
+        }
+        // Past the end of the ledger fewer transactions than asked for are returned.
+        let mut txns = self.debugger.get_committed_transactions(begin, limit)?;
+        let mut committed_outputs = self
+            .debugger
+            .get_committed_transaction_outputs(begin, txns.len() as u64)?;
+        if committed_outputs.len() != txns.len() {
+            bail!(
+                "Got {} committed outputs for the {} transactions starting at version {}",
+                committed_outputs.len(),
+                txns.len(),
+                begin
+            );
+        }
+        let mut ret = vec![];
+        while !txns.is_empty() {
+            let epoch_result = truncate_at_reconfiguration(
+                self.execute_transactions_with_overrides(begin, txns.clone(), &overrides)?,
+            );
+            let num_txns = epoch_result.len();
+            if num_txns == 0 {
+                break;
+            }
+            let remaining_outputs = committed_outputs.split_off(num_txns);
+            for (output, committed_output) in epoch_result.iter().zip(committed_outputs.iter()) {
+                ret.push(GasComparison {
+                    version: begin,
+                    committed_gas: committed_output.gas_used(),
+                    recomputed_gas: output.gas_used(),
+                    delta: output.gas_used() as i128 - committed_output.gas_used() as i128,
+                });
+                begin += 1;
+            }
+            txns = txns.split_off(num_txns);
+            committed_outputs = remaining_outputs;
+        }
+        Ok(ret)
+    }
+
+    /// Replay the transaction at `version` and attribute the gas it used to its entry point, see
+    /// `profile_transaction_at_version`.
+    pub fn gas_profile_at_version(&self, version: Version) -> Result<GasProfile> {
+        let mut txns = self.debugger.get_committed_transactions(version, 1)?;
+        let txn = match txns.pop() {
+            Some(txn) => txn,
+            None => bail!("No committed transaction found at version {}", version),
+        };
+        self.profile_transaction_at_version(version, txn)
+    }
+
+    /// Execute `txn` at `version` and break down the gas it used.
+    ///
+    /// The profile has a frame for the transaction, one for what it invoked (script function,
+    /// script, module bundle, ...) and below it the intrinsic gas charged for the transaction
+    /// size, computed from the gas schedule on chain, and the remainder spent executing.
+    ///
+    /// There is no per-instruction or per-native breakdown: the `GasStatus` of this VM only keeps
+    /// the gas left and offers no hook to observe individual charges, so attributing gas any
+    /// finer than this needs support in the Move VM first.
+    pub fn profile_transaction_at_version(
+        &self,
+        version: Version,
+        txn: Transaction,
+    ) -> Result<GasProfile> {
+        let intrinsic_gas = match (&txn, version.checked_sub(1)) {
+            (Transaction::UserTransaction(signed_txn), Some(base_version)) => {
+                let gas_constants = self
+                    .debugger
+                    .get_account_state_by_version(aptos_root_address(), base_version)?
+                    .ok_or_else(|| anyhow!("aptos_root_address doesn't exist"))?
+                    .get_config::<VMConfig>()?
+                    .ok_or_else(|| anyhow!("VMConfig doesn't exist"))?
+                    .gas_schedule
+                    .gas_constants;
+                let raw_bytes_len = AbstractMemorySize::new(signed_txn.raw_txn_bytes_len() as u64);
+                gas_constants
+                    .to_external_units(calculate_intrinsic_gas(raw_bytes_len, &gas_constants))
+                    .get()
+            }
+            _ => 0,
+        };
+        let frames = vec![format!("txn_{}", version), transaction_entry_point(&txn)];
+        let output = match self
+            .execute_transactions_at_version(version, vec![txn], None)?
+            .pop()
+        {
+            Some(output) => output,
+            None => bail!("No output produced at version {}", version),
+        };
+        let intrinsic_gas = std::cmp::min(intrinsic_gas, output.gas_used());
+        let mut profile = GasProfile::new();
+        if intrinsic_gas != 0 {
+            let mut stack = frames.clone();
+            stack.push("intrinsic".to_string());
+            profile.record(stack, intrinsic_gas);
+        }
+        let mut stack = frames;
+        stack.push("execution".to_string());
+        profile.record(stack, output.gas_used() - intrinsic_gas);
+        Ok(profile)
+    }
+
+    /// Write the gas profile of the transaction at `version` to `writer` in folded-stack format.
+    /// It only breaks the gas down into intrinsic and execution gas, so it is not a flamegraph of
+    /// the Move calls.
+    pub fn write_gas_profile_at_version<W: Write>(
+        &self,
+        version: Version,
+        writer: W,
+    ) -> Result<()> {
+        self.gas_profile_at_version(version)?
+            .write_folded(writer)
+            .map_err(Into::into)
+    }
+
+    /// Execute `txn` at `version` and return the transitive closure of modules it depends on.
+    /// The result contains every module loaded during execution plus all of their (transitive)
+    /// dependencies, which is enough to reproduce the transaction in a standalone environment.
+    pub fn collect_transaction_dependencies(
+        &self,
+        version: Version,
+        txn: Transaction,
+    ) -> Result<BTreeSet<ModuleId>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        let recording_view = ReadRecordingStateView::new(&state_view);
+        AptosVM::execute_block(vec![txn], &recording_view)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
+
+        let mut pending: Vec<ModuleId> = recording_view
+            .into_reads()
+            .into_iter()
+            .filter_map(|key| match key {
+                StateKey::AccessPath(ap) => match ap.get_path() {
+                    access_path::Path::Code(module_id) => Some(module_id),
+                    access_path::Path::Resource(_) => None,
+                },
+                _ => None,
+            })
+            .collect();
+        let mut result = BTreeSet::new();
+        while let Some(module_id) = pending.pop() {
+            if result.contains(&module_id) {
+                continue;
+            }
+            let bytes = match state_view
+                .get_state_value(&StateKey::AccessPath(AccessPath::from(&module_id)))?
+            {
+                Some(bytes) => bytes,
+                // The transaction probed a module that doesn't exist on chain.
+                None => continue,
+            };
+            let module = CompiledModule::deserialize(&bytes)
+                .map_err(|e| anyhow!("Failure deserializing module {}: {:?}", module_id, e))?;
+            pending.extend(module.immediate_dependencies());
+            result.insert(module_id);
+        }
+        Ok(result)
+    }
+
+    /// Replay `limit` transactions starting at `begin`, one epoch at a time. `progress`, if set, is
+    /// called with the first version and the number of remaining transactions before each epoch.
+    ///
+    /// With a checkpoint configured (see `with_checkpoint`), replay resumes after the last version
+    /// recorded there and only the outputs of the remaining transactions are returned.
+    pub fn execute_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        save_write_sets: bool,
+        progress: Option<Box<dyn FnMut(Version, u64)>>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let checkpoint = match &self.checkpoint_path {
+            Some(path) => Some((
+                path.as_path(),
+                ReplayCheckpoint::load(path, begin, limit)?
+                    .unwrap_or_else(|| ReplayCheckpoint::new(begin, limit)),
+            )),
+            None => None,
+        };
+        let start = match &checkpoint {
+            Some((_, checkpoint)) => begin + checkpoint.num_transactions,
+            None => begin,
+        };
+        let txns = self
+            .debugger
+            .get_committed_transactions(start, begin + limit - start)?;
+        self.execute_committed_transactions(start, txns, save_write_sets, progress, checkpoint)
+    }
+
+    /// Same as `execute_past_transactions`, but only the outputs of the transactions matching
+    /// `filter` are returned, together with their versions.
+    ///
+    /// The filter only applies to the returned results: every transaction in the range is still
+    /// executed, since the ones that don't match produce the state the matching ones run on.
+    pub fn execute_past_transactions_filtered<F>(
+        &self,
+        begin: Version,
+        limit: u64,
+        filter: F,
+    ) -> Result<Vec<(Version, TransactionOutput)>>
+    where
+        F: Fn(&Transaction) -> bool,
+    {
+        let txns = self.debugger.get_committed_transactions(begin, limit)?;
+        let matches: Vec<bool> = txns.iter().map(|txn| filter(txn)).collect();
+        let outputs = self.execute_committed_transactions(begin, txns, false, None, None)?;
+        Ok(outputs
+            .into_iter()
+            .zip(matches)
+            .enumerate()
+            .filter(|(_, (_, is_match))| *is_match)
+            .map(|(idx, (output, _))| (begin + idx as u64, output))
+            .collect())
+    }
+
+    /// Replay the committed transactions `txns` starting at `begin`, one epoch at a time, and
+    /// save the progress to `checkpoint` after each epoch.
+    fn execute_committed_transactions(
+        &self,
+        mut begin: Version,
+        mut txns: Vec<Transaction>,
+        save_write_sets: bool,
+        mut progress: Option<Box<dyn FnMut(Version, u64)>>,
+        mut checkpoint: Option<(&Path, ReplayCheckpoint)>,
+    ) -> Result<Vec<TransactionOutput>> {
+        let mut limit = txns.len() as u64;
+        let mut ret = vec![];
+        while limit != 0 {
+            if let Some(progress) = progress.as_mut() {
+                progress(begin, limit);
+            }
+            let mut epoch_result = self
+                .execute_transactions_by_epoch(begin, txns.clone(), save_write_sets)?
+                .outputs;
+            if let Some((path, checkpoint)) = checkpoint.as_mut() {
+                checkpoint.record(&epoch_result);
+                checkpoint.save(path)?;
+            }
+            begin += epoch_result.len() as u64;
+            limit -= epoch_result.len() as u64;
+            txns = txns.split_off(epoch_result.len());
+            ret.append(&mut epoch_result);
+        }
+        Ok(ret)
+    }
+
+    /// Async counterpart of `execute_past_transactions`, for use inside async services. The
+    /// validator interface is synchronous and the REST one drives its own tokio runtime, which
+    /// must not be blocked on from an async worker, so the whole call runs in tokio's blocking
+    /// pool via `spawn_blocking`; no I/O is awaited on the async side. Outputs are identical.
+    pub async fn execute_past_transactions_async(
+        self: Arc<Self>,
+        begin: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        spawn_blocking(move || self.execute_past_transactions(begin, limit, false, None)).await?
+    }
+
+    /// Async counterpart of `execute_transactions_at_version`, see
+    /// `execute_past_transactions_async`.
+    pub async fn execute_transactions_at_version_async(
+        self: Arc<Self>,
+        version: Version,
+        txns: Vec<Transaction>,
+        per_txn_timeout: Option<Duration>,
+    ) -> Result<Vec<TransactionOutput>> {
+        spawn_blocking(move || self.execute_transactions_at_version(version, txns, per_txn_timeout))
+            .await?
+    }
+
+    /// Async counterpart of `get_latest_version`, see `execute_past_transactions_async`.
+    pub async fn get_latest_version_async(self: Arc<Self>) -> Result<Version> {
+        spawn_blocking(move || self.get_latest_version()).await?
+    }
+
+    /// Replay `limit` transactions starting at `begin` and compare each output against the one
+    /// committed on chain. An empty result means the replay reproduced the chain exactly.
+    pub fn verify_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> Result<Vec<ReplayDivergence>> {
+        let txns = self.debugger.get_committed_transactions(begin, limit)?;
+        let replayed = self.execute_committed_transactions(begin, txns, false, None, None)?;
+        let committed = self
+            .debugger
+            .get_committed_transaction_outputs(begin, limit)?;
+        if replayed.len() != committed.len() {
+            bail!(
+                "Replayed {} transactions but {} were committed",
+                replayed.len(),
+                committed.len()
+            );
+        }
+        Ok(replayed
+            .iter()
+            .zip(committed.iter())
+            .enumerate()
+            .flat_map(|(idx, (replayed, committed))| {
+                diff_transaction_outputs(begin + idx as u64, replayed, committed)
+            })
+            .collect())
+    }
+
+    /// Replay `limit` transactions starting at `begin` and write each output to `writer` as a
+    /// line of JSON (see `ReplayOutputRecord`) as soon as it is produced, so the whole range
+    /// never has to fit in memory.
+    pub fn execute_past_transactions_to_writer<W: Write>(
         &self,
-        version: Version,
-        txns: Vec<Transaction>,
-    ) -> Result<Vec<TransactionOutput>> {
-        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
-        AptosVM::execute_block(txns, &state_view)
-            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
+        begin: Version,
+        limit: u64,
+        mut writer: W,
+    ) -> Result<()> {
+        for result in self.replay_iter(begin, limit) {
+            let (version, output) = result?;
+            serde_json::to_writer(&mut writer, &ReplayOutputRecord::new(version, &output)?)?;
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
     }
 
-    pub fn execute_past_transactions(
+    /// Same as `execute_past_transactions`, but epochs are replayed concurrently on a pool of
+    /// `num_threads` threads. The range is split at the reconfigurations recorded on chain, so
+    /// each chunk executes against the state at its own base version and none of them depends on
+    /// another. Outputs are returned in version order.
+    pub fn execute_past_transactions_parallel(
         &self,
-        mut begin: Version,
-        mut limit: u64,
-        save_write_sets: bool,
+        begin: Version,
+        limit: u64,
+        num_threads: usize,
     ) -> Result<Vec<TransactionOutput>> {
-        let mut txns = self.debugger.get_committed_transactions(begin, limit)?;
+        let chunks = self.split_at_committed_reconfigurations(begin, limit)?;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()?;
+        let results: Vec<Result<Vec<TransactionOutput>>> = pool.install(|| {
+            chunks
+                .into_par_iter()
+                .map(|chunk| {
+                    let txns = self
+                        .debugger
+                        .get_committed_transactions(chunk.start, chunk.end - chunk.start)?;
+                    self.execute_committed_transactions(chunk.start, txns, false, None, None)
+                })
+                .collect()
+        });
         let mut ret = vec![];
-        while limit != 0 {
-            println!(
-                "Starting epoch execution at {:?}, {:?} transactions remaining",
-                begin, limit
-            );
-            let mut epoch_result =
-                self.execute_transactions_by_epoch(begin, txns.clone(), save_write_sets)?;
-            begin += epoch_result.len() as u64;
-            limit -= epoch_result.len() as u64;
-            txns = txns.split_off(epoch_result.len());
-            ret.append(&mut epoch_result);
+        for result in results {
+            ret.append(&mut result?);
         }
         Ok(ret)
     }
 
+    /// Split `[begin, begin + limit)` into ranges that each end with a committed reconfiguration,
+    /// except possibly the last one.
+    fn split_at_committed_reconfigurations(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> Result<Vec<Range<Version>>> {
+        let end = begin + limit;
+        let mut chunks = vec![];
+        let mut chunk_start = begin;
+        let mut next_version = begin;
+        while next_version < end {
+            let batch_size = std::cmp::min(end - next_version, REPLAY_ITER_BATCH_SIZE);
+            let outputs = self
+                .debugger
+                .get_committed_transaction_outputs(next_version, batch_size)?;
+            if outputs.is_empty() {
+                bail!("No committed transaction found at version {}", next_version);
+            }
+            for output in outputs {
+                next_version += 1;
+                if is_reconfiguration(&output) {
+                    chunks.push(chunk_start..next_version);
+                    chunk_start = next_version;
+                }
+            }
+        }
+        if chunk_start < end {
+            chunks.push(chunk_start..end);
+        }
+        Ok(chunks)
+    }
+
+    /// Lazily replay `limit` transactions starting at `begin`. Transactions are fetched and executed
+    /// in bounded batches, so memory usage doesn't grow with the size of the range. The iterator
+    /// stops after yielding the first error.
+    pub fn replay_iter(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> impl Iterator<Item = Result<(Version, TransactionOutput)>> + '_ {
+        ReplayIter {
+            debugger: self,
+            next_version: begin,
+            remaining: limit,
+            buffered: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// Execute `txns` starting at version `begin`, keeping only the outputs up to and including
+    /// the first reconfiguration. Transactions after the reconfiguration would need to run
+    /// against the new epoch's configs, so they are reported in `skipped_versions` instead.
     pub fn execute_transactions_by_epoch(
         &self,
         begin: Version,
         txns: Vec<Transaction>,
         save_write_sets: bool,
-    ) -> Result<Vec<TransactionOutput>> {
-        let results = self.execute_transactions_at_version(begin, txns)?;
-        let mut ret = vec![];
-        let mut is_reconfig = false;
+    ) -> Result<EpochExecutionResult> {
+        let results = self.execute_transactions_at_version(begin, txns, None)?;
+        let num_txns = results.len() as u64;
 
         if save_write_sets {
             for result in &results {
@@ -113,16 +1356,17 @@ impl AptosDebugger {
             }
         }
 
-        for result in results.into_iter() {
-            if is_reconfig {
-                continue;
-            }
-            if is_reconfiguration(&result) {
-                is_reconfig = true;
-            }
-            ret.push(result)
-        }
-        Ok(ret)
+        let ret = truncate_at_reconfiguration(results);
+        let skipped_versions = begin + ret.len() as u64..begin + num_txns;
+        let reconfig_at = match ret.last() {
+            Some(output) if is_reconfiguration(output) => Some(begin + ret.len() as u64 - 1),
+            _ => None,
+        };
+        Ok(EpochExecutionResult {
+            outputs: ret,
+            skipped_versions,
+            reconfig_at,
+        })
     }
 
     pub fn execute_writeset_at_version(
@@ -169,14 +1413,22 @@ impl AptosDebugger {
 
     fn save_write_sets(&self, o: &TransactionOutput) -> Result<()> {
         let state_view = OnDiskStateView::create(&self.build_dir, &self.storage_dir)?;
+        let mut saved = vec![];
+        let mut deleted = vec![];
         for (key, op) in o.write_set() {
             let ap = AccessPath::try_from(key.clone())
                 .expect("State key can't be converted to access path");
             let addr = ap.address;
             match ap.get_path() {
                 access_path::Path::Resource(tag) => match op {
-                    WriteOp::Deletion => state_view.delete_resource(addr, tag)?,
-                    WriteOp::Value(bytes) => state_view.save_resource(addr, tag, bytes)?,
+                    WriteOp::Deletion => {
+                        deleted.push(ManifestEntry::new(addr, &tag));
+                        state_view.delete_resource(addr, tag)?
+                    }
+                    WriteOp::Value(bytes) => {
+                        saved.push(ManifestEntry::new(addr, &tag));
+                        state_view.save_resource(addr, tag, bytes)?
+                    }
                 },
                 access_path::Path::Code(module_id) => match op {
                     WriteOp::Deletion => state_view.delete_module(&module_id)?,
@@ -184,7 +1436,15 @@ impl AptosDebugger {
                 },
             }
         }
+        let mut indexed_events = vec![];
         for event in o.events() {
+            indexed_events.push((
+                event.type_tag().to_string(),
+                EventIndexEntry {
+                    key: hex::encode(event.key().as_bytes()),
+                    sequence_number: event.sequence_number(),
+                },
+            ));
             state_view.save_event(
                 event.key().as_bytes(),
                 event.sequence_number(),
@@ -192,6 +1452,50 @@ impl AptosDebugger {
                 event.event_data().to_vec(),
             )?
         }
+        self.update_manifest(saved, deleted)?;
+        self.update_events_index(indexed_events)
+    }
+
+    /// Add the saved events to the events index file, if enabled.
+    fn update_events_index(&self, events: Vec<(String, EventIndexEntry)>) -> Result<()> {
+        if !self.save_events_index {
+            return Ok(());
+        }
+        let path = self.storage_dir.join(EVENTS_INDEX_FILE_NAME);
+        let mut index: BTreeMap<String, BTreeSet<EventIndexEntry>> = if path.exists() {
+            serde_json::from_slice(&fs::read(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        for (type_tag, entry) in events {
+            index.entry(type_tag).or_default().insert(entry);
+        }
+        fs::create_dir_all(&self.storage_dir)?;
+        fs::write(&path, serde_json::to_vec_pretty(&index)?)?;
+        Ok(())
+    }
+
+    /// Merge the saved and deleted resources into the manifest file, if enabled.
+    fn update_manifest(
+        &self,
+        saved: Vec<ManifestEntry>,
+        deleted: Vec<ManifestEntry>,
+    ) -> Result<()> {
+        if !self.save_manifest {
+            return Ok(());
+        }
+        let path = self.storage_dir.join(MANIFEST_FILE_NAME);
+        let mut manifest: BTreeSet<ManifestEntry> = if path.exists() {
+            serde_json::from_slice(&fs::read(&path)?)?
+        } else {
+            BTreeSet::new()
+        };
+        for entry in &deleted {
+            manifest.remove(entry);
+        }
+        manifest.extend(saved);
+        fs::create_dir_all(&self.storage_dir)?;
+        fs::write(&path, serde_json::to_vec_pretty(&manifest)?)?;
         Ok(())
     }
 
@@ -201,16 +1505,18 @@ impl AptosDebugger {
         account_state: &AccountState,
     ) -> Result<()> {
         let disk_view = OnDiskStateView::create(&self.build_dir, &self.storage_dir)?;
+        let mut saved = vec![];
         for (key, value) in account_state.iter() {
             let key: access_path::Path = bcs::from_bytes(key)?;
             match key {
                 access_path::Path::Code(m) => disk_view.save_module(&m, value)?,
                 access_path::Path::Resource(struct_tag) => {
+                    saved.push(ManifestEntry::new(account, &struct_tag));
                     disk_view.save_resource(account, struct_tag, value)?
                 }
             }
         }
-        Ok(())
+        self.update_manifest(saved, vec![])
     }
 
     pub fn get_aptos_framework_modules_at_version(
@@ -230,11 +1536,35 @@ impl AptosDebugger {
         Ok(modules)
     }
 
+    /// Fetch the bytecode of `module_id` as of `version` and return its disassembly.
+    pub fn disassemble_module_at_version(
+        &self,
+        module_id: &ModuleId,
+        version: Version,
+    ) -> Result<String> {
+        let bytes = self
+            .debugger
+            .get_state_value_by_version(
+                &StateKey::AccessPath(AccessPath::from(module_id)),
+                version,
+            )?
+            .and_then(|state_value| state_value.maybe_bytes)
+            .ok_or_else(|| anyhow!("Module {} doesn't exist at version {}", module_id, version))?;
+        let module = CompiledModule::deserialize(&bytes)
+            .map_err(|e| anyhow!("Failure deserializing module {}: {:?}", module_id, e))?;
+        let source_mapping = SourceMapping::new_from_view(
+            BinaryIndexedView::Module(&module),
+            Spanned::unsafe_no_loc(()).loc,
+        )?;
+        Disassembler::new(source_mapping, DisassemblerOptions::new()).disassemble()
+    }
+
     pub fn pretty_print_events(
         &self,
         event_key: &EventKey,
         start_seq: u64,
         limit: u64,
+        check_gaps: bool,
     ) -> Result<()> {
         let events = self.debugger.get_events(event_key, start_seq, limit)?;
         let events_data = self.annotate_events(events.as_slice())?;
@@ -242,33 +1572,113 @@ impl AptosDebugger {
             println!("Transaction Version: {}", event.transaction_version);
             println!("Event payload: {}", event_data);
         }
+        if check_gaps {
+            let gaps = find_event_sequence_gaps(start_seq, events.as_slice());
+            if gaps.is_empty() {
+                println!("Event sequence numbers are contiguous");
+            } else {
+                println!("Missing event sequence numbers: {:?}", gaps);
+            }
+        }
         Ok(())
     }
 
+    /// Annotate `events`, resolving the layout of each one against the state right after the
+    /// transaction that emitted it, so events keep decoding correctly after their type changes.
     pub fn annotate_events(&self, events: &[EventWithVersion]) -> Result<Vec<AnnotatedMoveStruct>> {
-        let version = self.debugger.get_latest_version()?;
+        events
+            .iter()
+            .map(|event| self.annotate_event_at_version(&event.event, event.transaction_version))
+            .collect()
+    }
+
+    /// Annotate `events` using the type layouts as of `version`.
+    pub fn annotate_events_at_version(
+        &self,
+        events: &[EventWithVersion],
+        version: Version,
+    ) -> Result<Vec<AnnotatedMoveStruct>> {
+        events
+            .iter()
+            .map(|event| self.annotate_event_at_version(&event.event, version))
+            .collect()
+    }
+
+    fn annotate_event_at_version(
+        &self,
+        event: &ContractEvent,
+        version: Version,
+    ) -> Result<AnnotatedMoveStruct> {
         let state_view = DebuggerStateView::new(&*self.debugger, Some(version));
         let remote_storage = RemoteStorage::new(&state_view);
         let annotator = AptosValueAnnotator::new(&remote_storage);
-        let mut events_data = vec![];
-        for event in events {
-            match &event.event {
-                ContractEvent::V0(event_v0) => match event_v0.type_tag() {
-                    TypeTag::Struct(s) => {
-                        events_data.push(annotator.view_resource(s, event_v0.event_data())?)
+        match event {
+            ContractEvent::V0(event_v0) => match event_v0.type_tag() {
+                TypeTag::Struct(s) => annotator.view_resource(s, event_v0.event_data()),
+                ty => bail!("Unexpected TypeTag: got {:?}", ty),
+            },
+        }
+    }
+
+    /// Collect every event of type `event_type` emitted by the transactions in
+    /// `[begin, begin + limit)`, each annotated against the state of its own version.
+    pub fn collect_events_by_type(
+        &self,
+        begin: Version,
+        limit: u64,
+        event_type: &StructTag,
+    ) -> Result<Vec<(Version, AnnotatedMoveStruct)>> {
+        let mut events = vec![];
+        self.collect_events_by_type_with_sink(begin, limit, event_type, |version, event| {
+            events.push((version, event))
+        })?;
+        Ok(events)
+    }
+
+    /// Same as `collect_events_by_type`, but hands each matching event to `sink` as soon as it is
+    /// found instead of accumulating them. Transaction outputs are fetched in bounded batches, so
+    /// scans over wide ranges run in constant memory.
+    pub fn collect_events_by_type_with_sink<F>(
+        &self,
+        mut begin: Version,
+        mut limit: u64,
+        event_type: &StructTag,
+        mut sink: F,
+    ) -> Result<()>
+    where
+        F: FnMut(Version, AnnotatedMoveStruct),
+    {
+        while limit != 0 {
+            let batch_size = std::cmp::min(limit, REPLAY_ITER_BATCH_SIZE);
+            let outputs = self
+                .debugger
+                .get_committed_transaction_outputs(begin, batch_size)?;
+            if outputs.is_empty() {
+                bail!("No committed transaction found at version {}", begin);
+            }
+            let num_outputs = outputs.len() as u64;
+            for (idx, output) in outputs.iter().enumerate() {
+                let version = begin + idx as u64;
+                for event in output.events() {
+                    if matches!(event.type_tag(), TypeTag::Struct(s) if s == event_type) {
+                        sink(version, self.annotate_event_at_version(event, version)?);
                     }
-                    ty => bail!("Unexpected TypeTag: got {:?}", ty),
-                },
+                }
             }
+            begin += num_outputs;
+            limit -= num_outputs;
         }
-        Ok(events_data)
+        Ok(())
     }
 
+    /// Annotate the resources stored under `account` at `version`. If `save_annotated_json` is
+    /// set, the annotated state is also written to that file as pretty printed JSON.
     pub fn annotate_account_state_at_version(
         &self,
         account: AccountAddress,
         version: Version,
         save_write_sets: bool,
+        save_annotated_json: Option<PathBuf>,
     ) -> Result<Option<AnnotatedAccountStateBlob>> {
         let state_view = DebuggerStateView::new(&*self.debugger, Some(version));
         let remote_storage = RemoteStorage::new(&state_view);
@@ -282,13 +1692,97 @@ impl AptosDebugger {
                     if save_write_sets {
                         self.save_account_state(account, &account_state)?;
                     }
-                    Some(annotator.view_account_state(&account_state)?)
+                    let annotated = annotator.view_account_state(&account_state)?;
+                    if let Some(path) = save_annotated_json {
+                        fs::write(path, serde_json::to_string_pretty(&annotated.to_json())?)?;
+                    }
+                    Some(annotated)
                 }
                 None => None,
             },
         )
     }
 
+    /// Report the resources and modules of `account` that differ between `version_a` and
+    /// `version_b`. Resources are annotated against the state of their own version. An account
+    /// that doesn't exist at one of the versions is treated as empty there.
+    pub fn diff_account_state(
+        &self,
+        account: AccountAddress,
+        version_a: Version,
+        version_b: Version,
+    ) -> Result<Vec<ResourceDiff>> {
+        let state_a = self
+            .debugger
+            .get_account_state_by_version(account, version_a)?
+            .unwrap_or_default();
+        let state_b = self
+            .debugger
+            .get_account_state_by_version(account, version_b)?
+            .unwrap_or_default();
+        let state_view_a = DebuggerStateView::new(&*self.debugger, Some(version_a));
+        let remote_storage_a = RemoteStorage::new(&state_view_a);
+        let annotator_a = AptosValueAnnotator::new(&remote_storage_a);
+        let state_view_b = DebuggerStateView::new(&*self.debugger, Some(version_b));
+        let remote_storage_b = RemoteStorage::new(&state_view_b);
+        let annotator_b = AptosValueAnnotator::new(&remote_storage_b);
+
+        let keys: BTreeSet<&Vec<u8>> = state_a
+            .iter()
+            .chain(state_b.iter())
+            .map(|(key, _)| key)
+            .collect();
+        let mut diffs = vec![];
+        for key in keys {
+            let (before, after) = (state_a.get(key), state_b.get(key));
+            if before == after {
+                continue;
+            }
+            diffs.push(match access_path::Path::try_from(key)? {
+                access_path::Path::Resource(tag) => ResourceDiff::Resource {
+                    before: before
+                        .map(|blob| annotator_a.view_resource(&tag, blob))
+                        .transpose()?,
+                    after: after
+                        .map(|blob| annotator_b.view_resource(&tag, blob))
+                        .transpose()?,
+                    tag,
+                },
+                access_path::Path::Code(id) => ResourceDiff::Module {
+                    id,
+                    before: before.cloned(),
+                    after: after.cloned(),
+                },
+            });
+        }
+        Ok(diffs)
+    }
+
+    /// Try to annotate every resource stored under `account` at `version` and return the ones that
+    /// failed, together with the annotation error. Unlike `annotate_account_state_at_version`, this
+    /// doesn't stop at the first undecodable resource.
+    pub fn validate_account_resources(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Vec<(StructTag, anyhow::Error)>> {
+        let state_view = DebuggerStateView::new(&*self.debugger, Some(version));
+        let remote_storage = RemoteStorage::new(&state_view);
+        let annotator = AptosValueAnnotator::new(&remote_storage);
+        let account_state = self
+            .debugger
+            .get_account_state_by_version(account, version)?
+            .ok_or_else(|| anyhow!("Account {} doesn't exist at version {}", account, version))?;
+
+        let mut failures = vec![];
+        for (tag, blob) in account_state.get_resources() {
+            if let Err(err) = annotator.view_resource(&tag, blob) {
+                failures.push((tag, err));
+            }
+        }
+        Ok(failures)
+    }
+
     pub fn annotate_key_accounts_at_version(
         &self,
         version: Version,
@@ -309,6 +1803,28 @@ impl AptosDebugger {
         Ok(result)
     }
 
+    pub fn get_validator_set_at_version(&self, version: Version) -> Result<ValidatorSet> {
+        self.debugger
+            .get_account_state_by_version(aptos_root_address(), version)?
+            .ok_or_else(|| anyhow!("aptos_root_address doesn't exist"))?
+            .get_config::<ValidatorSet>()?
+            .ok_or_else(|| anyhow!("validator_config doesn't exist"))
+    }
+
+    /// Compute decentralization metrics over the voting power of the validator set at `version`.
+    pub fn voting_power_distribution_at_version(
+        &self,
+        version: Version,
+    ) -> Result<VotingPowerDistribution> {
+        let validator_set = self.get_validator_set_at_version(version)?;
+        Ok(VotingPowerDistribution::from_voting_powers(
+            validator_set
+                .payload()
+                .map(|info| info.consensus_voting_power())
+                .collect(),
+        ))
+    }
+
     pub fn get_latest_version(&self) -> Result<Version> {
         self.debugger.get_latest_version()
     }
@@ -321,6 +1837,35 @@ impl AptosDebugger {
         self.debugger.get_version_by_account_sequence(account, seq)
     }
 
+    /// Replay the transaction that `account` submitted with sequence number `seq`.
+    ///
+    /// Only that transaction is replayed, on top of the committed state right before its version.
+    /// Transactions earlier in the same block are not re-executed, so their committed effects are
+    /// what the replayed transaction sees.
+    pub fn replay_transaction_by_account_sequence(
+        &self,
+        account: AccountAddress,
+        seq: u64,
+    ) -> Result<TransactionOutput> {
+        let version = self
+            .get_version_by_account_sequence(account, seq)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "No transaction found for account {} at sequence number {}",
+                    account,
+                    seq
+                )
+            })?;
+        let mut txns = self.debugger.get_committed_transactions(version, 1)?;
+        let txn = match txns.pop() {
+            Some(txn) => txn,
+            None => bail!("No committed transaction found at version {}", version),
+        };
+        self.execute_transactions_at_version(version, vec![txn], None)?
+            .pop()
+            .ok_or_else(|| anyhow!("No output produced at version {}", version))
+    }
+
     pub fn run_session_at_version<F>(
         &self,
         version: Version,
@@ -347,6 +1892,73 @@ impl AptosDebugger {
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    /// Same as `run_session_at_version`, but also returns the resources written by the session,
+    /// annotated with the struct layouts at the version the session ran against. Module writes
+    /// and table items are not resources and are only part of the `ChangeSet`.
+    pub fn run_session_at_version_annotated<F>(
+        &self,
+        version: Version,
+        override_changeset: Option<MoveChanges>,
+        f: F,
+    ) -> Result<(ChangeSet, Vec<(AccessPath, AnnotatedWrite)>)>
+    where
+        F: FnOnce(
+            &mut Session<DeltaStorage<RemoteStorage<DebuggerStateView>>>,
+        ) -> VMResult<SerializedReturnValues>,
+    {
+        let change_set = self.run_session_at_version(version, override_changeset, f)?;
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        let remote_storage = RemoteStorage::new(&state_view);
+        let annotator = AptosValueAnnotator::new(&remote_storage);
+        let mut writes = vec![];
+        for (key, op) in change_set.write_set() {
+            let ap = match key {
+                StateKey::AccessPath(ap) => ap.clone(),
+                StateKey::TableItem { .. } | StateKey::Raw(_) => continue,
+            };
+            let tag = match ap.get_path() {
+                access_path::Path::Resource(tag) => tag,
+                access_path::Path::Code(_) => continue,
+            };
+            let write = match op {
+                WriteOp::Deletion => AnnotatedWrite::Deletion,
+                WriteOp::Value(bytes) => {
+                    AnnotatedWrite::Value(annotator.view_resource(&tag, bytes)?)
+                }
+            };
+            writes.push((ap, write));
+        }
+        Ok((change_set, writes))
+    }
+
+    /// Same as `run_session_at_version`, but `f` also receives the `GasStatus` to execute with.
+    /// It meters against `gas_schedule` with a budget of `max_gas` units, which reproduces
+    /// out-of-gas failures seen on chain; `None` runs unmetered like `run_session_at_version`.
+    /// The `GasStatus` of this VM only tracks the gas left, so `f` can read the total consumed
+    /// from `GasStatus::remaining_gas` but not what it was charged for.
+    pub fn run_metered_session_at_version<F>(
+        &self,
+        version: Version,
+        override_changeset: Option<MoveChanges>,
+        gas_schedule: Option<&CostTable>,
+        max_gas: u64,
+        f: F,
+    ) -> Result<ChangeSet>
+    where
+        F: FnOnce(
+            &mut Session<DeltaStorage<RemoteStorage<DebuggerStateView>>>,
+            &mut GasStatus,
+        ) -> VMResult<SerializedReturnValues>,
+    {
+        let mut gas_status = match gas_schedule {
+            Some(cost_table) => GasStatus::new(cost_table, GasUnits::new(max_gas)),
+            None => GasStatus::new_unmetered(),
+        };
+        self.run_session_at_version(version, override_changeset, |session| {
+            f(session, &mut gas_status)
+        })
+    }
+
     pub fn bisect_transactions_by_script(
         &self,
         code_path: &str,
@@ -354,6 +1966,7 @@ impl AptosDebugger {
         begin: Version,
         end: Version,
         override_changeset: Option<MoveChanges>,
+        direction: BisectDirection,
     ) -> Result<Option<Version>> {
         // TODO: The code here is compiled against the local move stdlib instead of the one from on
         // chain storage.
@@ -371,15 +1984,40 @@ impl AptosDebugger {
             .map(|_| ())
         };
 
-        self.bisect_transaction_impl(is_version_ok, begin, end)
+        self.bisect_transaction_impl(is_version_ok, begin, end, direction)
+    }
+
+    /// Same as `bisect_transactions_by_script`, but the condition is a Rust `predicate` evaluated
+    /// against the debugger at each probed version, e.g. on top of
+    /// `annotate_account_state_at_version`. A version fails when the predicate returns `false` or
+    /// an error.
+    pub fn bisect_transactions_by_predicate<F>(
+        &self,
+        begin: Version,
+        end: Version,
+        predicate: F,
+        direction: BisectDirection,
+    ) -> Result<Option<Version>>
+    where
+        F: Fn(&AptosDebugger, Version) -> Result<bool>,
+    {
+        let is_version_ok = |version| {
+            if predicate(self, version)? {
+                Ok(())
+            } else {
+                bail!("Predicate doesn't hold at version {}", version)
+            }
+        };
+        self.bisect_transaction_impl(is_version_ok, begin, end, direction)
     }
 
     /// Find the first version between [begin, end) that nullify the predicate using binary search.
     fn bisect_transaction_impl<F>(
         &self,
         predicate: F,
-        mut begin: Version,
-        mut end: Version,
+        begin: Version,
+        end: Version,
+        direction: BisectDirection,
     ) -> Result<Option<Version>>
     where
         F: Fn(Version) -> Result<()>,
@@ -388,28 +2026,196 @@ impl AptosDebugger {
             bail!("Unexpected Version");
         }
 
-        let mut result = None;
-        while begin < end {
-            let mid = begin + (end - begin) / 2;
+        let mut first_failing = None;
+        let (mut low, mut high) = (begin, end);
+        while low < high {
+            let mid = low + (high - low) / 2;
             let mid_result = predicate(mid);
             println!("Checking Version: {:?}, got {:?}", mid, mid_result);
             if mid_result.is_err() {
-                result = Some(mid);
-                end = mid;
+                first_failing = Some(mid);
+                high = mid;
             } else {
-                begin = mid + 1;
+                low = mid + 1;
             }
         }
-        Ok(result)
+        Ok(match direction {
+            BisectDirection::FirstFailing => first_failing,
+            // The version right before the first failing one, if the predicate held anywhere.
+            BisectDirection::LastPassing => match first_failing.unwrap_or(end) {
+                first_failing if first_failing > begin => Some(first_failing - 1),
+                _ => None,
+            },
+        })
     }
 }
 
-fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
-    let new_epoch_event_key = aptos_types::on_chain_config::new_epoch_event_key();
-    vm_output
-        .events()
+struct ReplayIter<'a> {
+    debugger: &'a AptosDebugger,
+    next_version: Version,
+    remaining: u64,
+    buffered: VecDeque<(Version, TransactionOutput)>,
+    failed: bool,
+}
+
+impl<'a> ReplayIter<'a> {
+    fn fetch_next_batch(&mut self) -> Result<()> {
+        let batch_size = std::cmp::min(self.remaining, REPLAY_ITER_BATCH_SIZE);
+        let txns = self
+            .debugger
+            .debugger
+            .get_committed_transactions(self.next_version, batch_size)?;
+        let outputs = self
+            .debugger
+            .execute_transactions_by_epoch(self.next_version, txns, false)?
+            .outputs;
+        if outputs.is_empty() {
+            bail!("No output produced at version {}", self.next_version);
+        }
+        for output in outputs {
+            self.buffered.push_back((self.next_version, output));
+            self.next_version += 1;
+            self.remaining -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for ReplayIter<'a> {
+    type Item = Result<(Version, TransactionOutput)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if self.buffered.is_empty() && self.remaining > 0 {
+            if let Err(err) = self.fetch_next_batch() {
+                self.failed = true;
+                return Some(Err(err));
+            }
+        }
+        self.buffered.pop_front().map(Ok)
+    }
+}
+
+/// A `StateView` wrapper that records every key read through it.
+struct ReadRecordingStateView<'a, S> {
+    state_view: &'a S,
+    reads: Mutex<HashSet<StateKey>>,
+}
+
+impl<'a, S: StateView> ReadRecordingStateView<'a, S> {
+    fn new(state_view: &'a S) -> Self {
+        Self {
+            state_view,
+            reads: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn into_reads(self) -> HashSet<StateKey> {
+        self.reads.into_inner().expect("Lock poisoned")
+    }
+}
+
+impl<'a, S: StateView> StateView for ReadRecordingStateView<'a, S> {
+    fn id(&self) -> StateViewId {
+        self.state_view.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        self.reads
+            .lock()
+            .expect("Lock poisoned")
+            .insert(state_key.clone());
+        self.state_view.get_state_value(state_key)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.state_view.is_genesis()
+    }
+}
+
+/// Return the sequence numbers missing from `events`, assuming they should form a contiguous range
+/// starting at `start_seq`. Sequence numbers past the last returned event are not reported, as the
+/// stream may simply end there.
+pub fn find_event_sequence_gaps(start_seq: u64, events: &[EventWithVersion]) -> Vec<u64> {
+    let seqs: BTreeSet<u64> = events
         .iter()
-        .any(|event| *event.key() == new_epoch_event_key)
+        .map(|event| event.event.sequence_number())
+        .collect();
+    let last_seq = match seqs.iter().next_back() {
+        Some(seq) => *seq,
+        None => return vec![],
+    };
+    (start_seq..last_seq)
+        .filter(|seq| !seqs.contains(seq))
+        .collect()
+}
+
+/// A `StateView` that serves the values in `overrides` in place of the underlying state.
+struct OverrideStateView<'a, S> {
+    state_view: &'a S,
+    overrides: &'a HashMap<StateKey, Option<Vec<u8>>>,
+}
+
+impl<'a, S: StateView> OverrideStateView<'a, S> {
+    fn new(state_view: &'a S, overrides: &'a HashMap<StateKey, Option<Vec<u8>>>) -> Self {
+        Self {
+            state_view,
+            overrides,
+        }
+    }
+}
+
+impl<'a, S: StateView> StateView for OverrideStateView<'a, S> {
+    fn id(&self) -> StateViewId {
+        self.state_view.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        match self.overrides.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.state_view.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.state_view.is_genesis()
+    }
+}
+
+/// Keep the outputs up to and including the first reconfiguration, the remaining ones were
+/// computed against the configs of the previous epoch.
+fn truncate_at_reconfiguration(outputs: Vec<TransactionOutput>) -> Vec<TransactionOutput> {
+    let mut ret = vec![];
+    for output in outputs {
+        let is_reconfig = is_reconfiguration(&output);
+        ret.push(output);
+        if is_reconfig {
+            break;
+        }
+    }
+    ret
+}
+
+fn transaction_entry_point(txn: &Transaction) -> String {
+    match txn {
+        Transaction::UserTransaction(signed_txn) => match signed_txn.payload() {
+            TransactionPayload::ScriptFunction(script_fn) => {
+                format!("{}::{}", script_fn.module(), script_fn.function())
+            }
+            TransactionPayload::Script(_) => "script".to_string(),
+            TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+            TransactionPayload::WriteSet(_) => "write_set".to_string(),
+        },
+        Transaction::GenesisTransaction(_) => "genesis".to_string(),
+        Transaction::BlockMetadata(_) => "block_metadata".to_string(),
+        Transaction::StateCheckpoint => "state_checkpoint".to_string(),
+    }
+}
+
+fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
+    contains_new_epoch_event(vm_output.events())
 }
 
 fn compile_move_script(file_path: &str) -> Result<Vec<u8>> {
@@ -2,15 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{bail, Result};
-use aptos_transaction_replay::AptosDebugger;
+use aptos_transaction_replay::{AptosDebugger, BisectDirection};
 use aptos_types::{
     account_address::AccountAddress,
     event::EventKey,
     transaction::{TransactionPayload, Version},
 };
 use difference::Changeset;
-use move_deps::move_core_types::effects::ChangeSet;
-use std::{fs, path::PathBuf};
+use move_deps::move_core_types::{
+    effects::ChangeSet, identifier::Identifier, language_storage::ModuleId,
+};
+use std::{fs, io::BufWriter, path::PathBuf};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -18,9 +20,24 @@ struct Opt {
     /// Path to the local AptosDB file
     #[structopt(long, parse(from_os_str))]
     db: Option<PathBuf>,
+    /// URL of a fullnode REST endpoint to read from when no local AptosDB is provided
+    #[structopt(long)]
+    rest_endpoint: Option<String>,
     /// If true, persist the effects of replaying transactions via `cmd` to disk in a format understood by the Move CLI
     #[structopt(short = "s", global = true)]
     save_write_sets: bool,
+    /// If true, maintain a JSON manifest of the resources saved via `-s`
+    #[structopt(long, global = true)]
+    manifest: bool,
+    /// If true, maintain a JSON index of the events saved via `-s`, grouped by type
+    #[structopt(long, global = true)]
+    events_index: bool,
+    /// Record the progress of replaying transactions in this file and resume from it on restart
+    #[structopt(long, global = true, parse(from_os_str))]
+    checkpoint: Option<PathBuf>,
+    /// Number of historical state values to keep in memory, 0 disables the cache
+    #[structopt(long, global = true, default_value = "0")]
+    state_cache_size: usize,
     #[structopt(subcommand)] // Note that we mark a field as a subcommand
     cmd: Command,
 }
@@ -29,7 +46,17 @@ struct Opt {
 enum Command {
     /// Replay transactions starting from version `start` to `start + limit`.
     #[structopt(name = "replay-transactions")]
-    ReplayTransactions { start: Version, limit: u64 },
+    ReplayTransactions {
+        start: Version,
+        limit: u64,
+        /// Stream the outputs to this file as newline-delimited JSON instead of printing them
+        #[structopt(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+    /// Replay transactions from `start` to `start + limit` and report where the replayed outputs
+    /// differ from the committed ones.
+    #[structopt(name = "verify-transactions")]
+    VerifyTransactions { start: Version, limit: u64 },
     /// Replay the last `txns` committed transactions.
     #[structopt(name = "replay-recent-transactions")]
     ReplayRecentTransactions { txns: u64 },
@@ -54,13 +81,30 @@ enum Command {
         #[structopt(parse(try_from_str))]
         account: AccountAddress,
         version: Option<Version>,
+        /// Also write the annotated state to this file as JSON
+        #[structopt(long, parse(from_os_str))]
+        json: Option<PathBuf>,
+    },
+    /// List the resources stored under `account` at `version` that fail to annotate.
+    #[structopt(name = "validate-account")]
+    ValidateAccount {
+        #[structopt(parse(try_from_str))]
+        account: AccountAddress,
+        version: Option<Version>,
     },
     /// Annotate the resources stored under `aptos_root`, and all validator addresses.
     #[structopt(name = "annotate-key-accounts")]
     AnnotateKeyAccounts { version: Version },
     /// Annotate the events stored under `key` with range `start` to `start+limit`.
     #[structopt(name = "annotate-events")]
-    AnnotateEvents { key: String, start: u64, limit: u64 },
+    AnnotateEvents {
+        key: String,
+        start: u64,
+        limit: u64,
+        /// Report missing sequence numbers in the returned events
+        #[structopt(long)]
+        check_gaps: bool,
+    },
     /// Diff between the resources stored under two versions of the same `account`
     #[structopt(name = "diff-account")]
     DiffAccount {
@@ -72,6 +116,14 @@ enum Command {
     /// Get the bytecode for all Framework modules at `version`
     #[structopt(name = "get-modules")]
     GetModules { version: Version },
+    /// Print the disassembled bytecode of module `address::name` at `version`
+    #[structopt(name = "disassemble-module")]
+    DisassembleModule {
+        #[structopt(parse(try_from_str))]
+        address: AccountAddress,
+        name: String,
+        version: Option<Version>,
+    },
     #[structopt(name = "bisect-transaction")]
     BisectTransaction {
         #[structopt(parse(from_os_str))]
@@ -82,26 +134,83 @@ enum Command {
         end: Version,
         #[structopt(long)]
         rebuild_stdlib: bool,
+        /// Report the last version where the script succeeds instead of the first where it fails
+        #[structopt(long)]
+        last_passing: bool,
     },
 }
 
+fn print_progress(begin: Version, limit: u64) {
+    println!(
+        "Starting epoch execution at {:?}, {:?} transactions remaining",
+        begin, limit
+    );
+}
+
 fn main() -> Result<()> {
     let opt = Opt::from_args();
     let debugger = if let Some(p) = opt.db {
         AptosDebugger::db(p)?
+    } else if let Some(url) = opt.rest_endpoint.as_deref() {
+        AptosDebugger::rest(url)?
     } else {
         panic!("No debugger attached")
+    }
+    .with_manifest(opt.manifest)
+    .with_events_index(opt.events_index);
+
+    let debugger = match opt.checkpoint {
+        Some(path) => debugger.with_checkpoint(path),
+        None => debugger,
+    };
+
+    let debugger = if opt.state_cache_size > 0 {
+        debugger.with_state_cache(opt.state_cache_size)
+    } else {
+        debugger
     };
 
     println!("Connection Succeeded");
 
     match opt.cmd {
-        Command::ReplayTransactions { start, limit } => {
+        Command::ReplayTransactions {
+            start,
+            limit,
+            output: Some(path),
+        } => {
+            debugger.execute_past_transactions_to_writer(
+                start,
+                limit,
+                BufWriter::new(fs::File::create(path)?),
+            )?;
+        }
+        Command::ReplayTransactions {
+            start,
+            limit,
+            output: None,
+        } => {
             println!(
                 "{:#?}",
-                debugger.execute_past_transactions(start, limit, opt.save_write_sets)
+                debugger.execute_past_transactions(
+                    start,
+                    limit,
+                    opt.save_write_sets,
+                    Some(Box::new(print_progress))
+                )
             );
         }
+        Command::VerifyTransactions { start, limit } => {
+            let divergences = debugger.verify_past_transactions(start, limit)?;
+            if divergences.is_empty() {
+                println!("Replay matches the committed outputs");
+            }
+            for divergence in divergences {
+                println!(
+                    "Version {}: {:?} diverged, {}",
+                    divergence.version, divergence.field, divergence.summary
+                );
+            }
+        }
         Command::ReplayRecentTransactions { txns } => {
             let latest_version = debugger
                 .get_latest_version()
@@ -112,7 +221,8 @@ fn main() -> Result<()> {
                 debugger.execute_past_transactions(
                     latest_version - txns,
                     txns,
-                    opt.save_write_sets
+                    opt.save_write_sets,
+                    Some(Box::new(print_progress))
                 )
             );
         }
@@ -123,7 +233,12 @@ fn main() -> Result<()> {
             println!(
                 "Executing transaction at version: {:?}\n{:#?}",
                 version,
-                debugger.execute_past_transactions(version, 1, opt.save_write_sets)
+                debugger.execute_past_transactions(
+                    version,
+                    1,
+                    opt.save_write_sets,
+                    Some(Box::new(print_progress))
+                )
             );
         }
         Command::ReplayWriteSetAtVersion {
@@ -148,6 +263,7 @@ fn main() -> Result<()> {
         Command::AnnotateAccount {
             account,
             version: version_opt,
+            json,
         } => {
             let version = match version_opt {
                 Some(v) => v,
@@ -156,15 +272,37 @@ fn main() -> Result<()> {
             println!(
                 "{}",
                 debugger
-                    .annotate_account_state_at_version(account, version, opt.save_write_sets)?
+                    .annotate_account_state_at_version(account, version, opt.save_write_sets, json)?
                     .expect("Account not found")
             )
         }
-        Command::AnnotateEvents { key, start, limit } => {
+        Command::ValidateAccount {
+            account,
+            version: version_opt,
+        } => {
+            let version = match version_opt {
+                Some(v) => v,
+                None => debugger.get_latest_version()?,
+            };
+            let failures = debugger.validate_account_resources(account, version)?;
+            if failures.is_empty() {
+                println!("All resources under {} annotated successfully", account);
+            }
+            for (tag, err) in failures {
+                println!("Failed to annotate {}: {:?}", tag, err);
+            }
+        }
+        Command::AnnotateEvents {
+            key,
+            start,
+            limit,
+            check_gaps,
+        } => {
             debugger.pretty_print_events(
                 &EventKey::from_bytes(hex::decode(key.as_str())?)?,
                 start,
                 limit,
+                check_gaps,
             )?;
         }
         Command::AnnotateKeyAccounts { version } => {
@@ -182,13 +320,13 @@ fn main() -> Result<()> {
             let base_annotation = format!(
                 "{}",
                 debugger
-                    .annotate_account_state_at_version(account, base_version, false)?
+                    .annotate_account_state_at_version(account, base_version, false, None)?
                     .expect("Account not found")
             );
             let revision_annotation = format!(
                 "{}",
                 debugger
-                    .annotate_account_state_at_version(account, revision, false)?
+                    .annotate_account_state_at_version(account, revision, false, None)?
                     .expect("Account not found")
             );
             println!(
@@ -201,12 +339,28 @@ fn main() -> Result<()> {
                 debugger.get_aptos_framework_modules_at_version(version, opt.save_write_sets)?;
             println!("Fetched {} modules", modules.len())
         }
+        Command::DisassembleModule {
+            address,
+            name,
+            version: version_opt,
+        } => {
+            let version = match version_opt {
+                Some(v) => v,
+                None => debugger.get_latest_version()?,
+            };
+            let module_id = ModuleId::new(address, Identifier::new(name)?);
+            println!(
+                "{}",
+                debugger.disassemble_module_at_version(&module_id, version)?
+            );
+        }
         Command::BisectTransaction {
             sender,
             script_path,
             begin,
             end,
             rebuild_stdlib: reload_stdlib,
+            last_passing,
         } => println!(
             "{:?}",
             debugger.bisect_transactions_by_script(
@@ -225,6 +379,11 @@ fn main() -> Result<()> {
                 } else {
                     None
                 },
+                if last_passing {
+                    BisectDirection::LastPassing
+                } else {
+                    BisectDirection::FirstFailing
+                },
             )
         ),
     }
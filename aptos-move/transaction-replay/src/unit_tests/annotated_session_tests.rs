@@ -0,0 +1,47 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{unit_tests::TestInterface, AptosDebugger};
+use aptos_types::{state_store::state_key::StateKey, write_set::WriteOp};
+use move_deps::{
+    move_core_types::{
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag, CORE_CODE_ADDRESS},
+    },
+    move_vm_types::gas_schedule::GasStatus,
+};
+
+#[test]
+fn test_annotated_session_with_table_item() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    let handle = 1u128;
+    let (change_set, writes) = debugger
+        .run_session_at_version_annotated(1, None, |session| {
+            session.execute_function_bypass_visibility(
+                &ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("Table").unwrap()),
+                &Identifier::new("add").unwrap(),
+                vec![TypeTag::U64, TypeTag::U64],
+                vec![
+                    bcs::to_bytes(&(handle, 0u64)).unwrap(),
+                    bcs::to_bytes(&2u64).unwrap(),
+                    bcs::to_bytes(&3u64).unwrap(),
+                ],
+                &mut GasStatus::new_unmetered(),
+            )
+        })
+        .unwrap();
+
+    let table_items: Vec<_> = change_set
+        .write_set()
+        .iter()
+        .filter(|(key, _)| matches!(key, StateKey::TableItem { .. }))
+        .collect();
+    assert_eq!(
+        table_items,
+        vec![&(
+            StateKey::table_item(handle, bcs::to_bytes(&2u64).unwrap()),
+            WriteOp::Value(bcs::to_bytes(&3u64).unwrap()),
+        )]
+    );
+    assert!(writes.is_empty());
+}
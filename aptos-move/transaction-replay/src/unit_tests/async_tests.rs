@@ -0,0 +1,29 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{unit_tests::TestInterface, AptosDebugger};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_async_variants_match_sync() {
+    let debugger = Arc::new(AptosDebugger::new(Box::new(TestInterface::genesis())));
+
+    // The range runs past the end of the ledger, which only has the genesis.
+    let outputs = debugger
+        .clone()
+        .execute_past_transactions_async(0, 10)
+        .await
+        .unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(
+        outputs,
+        debugger
+            .execute_past_transactions(0, 10, false, None)
+            .unwrap()
+    );
+
+    assert_eq!(
+        debugger.clone().get_latest_version_async().await.unwrap(),
+        debugger.get_latest_version().unwrap()
+    );
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{unit_tests::TestInterface, AptosDebugger};
+use crate::{unit_tests::TestInterface, AptosDebugger, BisectDirection};
 use anyhow::bail;
 use aptos_types::{account_address::AccountAddress, account_config::AccountResource};
 use move_deps::move_core_types::{effects::ChangeSet, move_resource::MoveStructType};
@@ -10,7 +10,7 @@ use std::path::PathBuf;
 #[test]
 fn test_bisection() {
     let debugger = AptosDebugger::new(Box::new(TestInterface::empty(100)));
-    let check = |v: Vec<bool>, result| {
+    let check_direction = |v: Vec<bool>, direction, result| {
         assert_eq!(
             debugger
                 .bisect_transaction_impl(
@@ -22,16 +22,49 @@ fn test_bisection() {
                         }
                     },
                     0,
-                    v.len() as u64
+                    v.len() as u64,
+                    direction
                 )
                 .unwrap(),
             result
         );
     };
+    let check = |v: Vec<bool>, result| check_direction(v, BisectDirection::FirstFailing, result);
     check(vec![true, true, true, true], None);
     check(vec![true, true, true, false], Some(3));
     check(vec![true, true, false, false], Some(2));
     check(vec![false, false, false, false], Some(0));
+
+    let check_last_passing =
+        |v: Vec<bool>, result| check_direction(v, BisectDirection::LastPassing, result);
+    check_last_passing(vec![], None);
+    check_last_passing(vec![true, true, true, true], Some(3));
+    check_last_passing(vec![true, true, true, false], Some(2));
+    check_last_passing(vec![true, false, false, false], Some(0));
+    check_last_passing(vec![false, false, false, false], None);
+}
+
+#[test]
+fn test_bisection_by_predicate() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::empty(100)));
+    let predicate = |_: &AptosDebugger, version| {
+        if version == 7 {
+            bail!("Err")
+        }
+        Ok(version < 5)
+    };
+    assert_eq!(
+        debugger
+            .bisect_transactions_by_predicate(0, 10, predicate, BisectDirection::FirstFailing)
+            .unwrap(),
+        Some(5)
+    );
+    assert_eq!(
+        debugger
+            .bisect_transactions_by_predicate(0, 10, predicate, BisectDirection::LastPassing)
+            .unwrap(),
+        Some(4)
+    );
 }
 
 #[test]
@@ -53,7 +86,14 @@ fn test_changeset_override() {
     assert_eq!(
         None,
         debugger
-            .bisect_transactions_by_script(script_path.to_str().unwrap(), address, 1, 2, None)
+            .bisect_transactions_by_script(
+                script_path.to_str().unwrap(),
+                address,
+                1,
+                2,
+                None,
+                BisectDirection::FirstFailing
+            )
             .unwrap()
     );
     assert_eq!(
@@ -64,7 +104,8 @@ fn test_changeset_override() {
                 address,
                 1,
                 2,
-                Some(override_changeset)
+                Some(override_changeset),
+                BisectDirection::FirstFailing
             )
             .unwrap()
     );
@@ -0,0 +1,42 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ReplayCheckpoint;
+use aptos_temppath::TempPath;
+
+#[test]
+fn test_checkpoint_round_trip() {
+    let path = TempPath::new();
+    assert_eq!(ReplayCheckpoint::load(path.path(), 10, 100).unwrap(), None);
+
+    let checkpoint = ReplayCheckpoint {
+        format_version: ReplayCheckpoint::FORMAT_VERSION,
+        begin: 10,
+        limit: 100,
+        num_transactions: 5,
+        total_gas_used: 42,
+    };
+    assert_eq!(checkpoint.last_version(), Some(14));
+    checkpoint.save(path.path()).unwrap();
+    assert_eq!(
+        ReplayCheckpoint::load(path.path(), 10, 100).unwrap(),
+        Some(checkpoint)
+    );
+    // A checkpoint for another range must not be resumed from.
+    assert!(ReplayCheckpoint::load(path.path(), 11, 100).is_err());
+}
+
+#[test]
+fn test_checkpoint_rejects_unknown_format() {
+    let path = TempPath::new();
+    ReplayCheckpoint {
+        format_version: ReplayCheckpoint::FORMAT_VERSION + 1,
+        begin: 0,
+        limit: 1,
+        num_transactions: 0,
+        total_gas_used: 0,
+    }
+    .save(path.path())
+    .unwrap();
+    assert!(ReplayCheckpoint::load(path.path(), 0, 1).is_err());
+}
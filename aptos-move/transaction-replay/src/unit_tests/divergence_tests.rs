@@ -0,0 +1,59 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{diff_transaction_outputs, DivergentField};
+use aptos_types::{
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    write_set::{WriteOp, WriteSetMut},
+};
+
+fn output(writes: Vec<(u8, WriteOp)>, gas_used: u64, status: ExecutionStatus) -> TransactionOutput {
+    let write_set = WriteSetMut::new(
+        writes
+            .into_iter()
+            .map(|(key, op)| (StateKey::Raw(vec![key]), op))
+            .collect(),
+    )
+    .freeze()
+    .unwrap();
+    TransactionOutput::new(write_set, vec![], gas_used, TransactionStatus::Keep(status))
+}
+
+#[test]
+fn test_identical_outputs_do_not_diverge() {
+    let committed = output(
+        vec![(1, WriteOp::Value(vec![1]))],
+        10,
+        ExecutionStatus::Success,
+    );
+    assert!(diff_transaction_outputs(5, &committed.clone(), &committed).is_empty());
+}
+
+#[test]
+fn test_diverging_outputs() {
+    let replayed = output(
+        vec![(1, WriteOp::Value(vec![2])), (2, WriteOp::Deletion)],
+        12,
+        ExecutionStatus::OutOfGas,
+    );
+    let committed = output(
+        vec![(1, WriteOp::Value(vec![1])), (3, WriteOp::Deletion)],
+        10,
+        ExecutionStatus::Success,
+    );
+    let divergences = diff_transaction_outputs(5, &replayed, &committed);
+    assert!(divergences.iter().all(|d| d.version == 5));
+    assert_eq!(
+        divergences.iter().map(|d| d.field).collect::<Vec<_>>(),
+        vec![
+            DivergentField::WriteSet,
+            DivergentField::GasUsed,
+            DivergentField::Status
+        ]
+    );
+    assert_eq!(
+        divergences[0].summary,
+        "1 keys only written by replay, 1 only written on chain, 1 written differently"
+    );
+}
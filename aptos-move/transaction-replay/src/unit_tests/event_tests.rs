@@ -0,0 +1,33 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::find_event_sequence_gaps;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    event::EventKey,
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+
+fn events_with_seqs(seqs: &[u64]) -> Vec<EventWithVersion> {
+    let key = EventKey::new_from_address(&AccountAddress::random(), 0);
+    seqs.iter()
+        .map(|seq| {
+            EventWithVersion::new(*seq, ContractEvent::new(key, *seq, TypeTag::Bool, vec![]))
+        })
+        .collect()
+}
+
+#[test]
+fn test_event_sequence_gaps() {
+    assert!(find_event_sequence_gaps(0, &[]).is_empty());
+    assert!(find_event_sequence_gaps(3, &events_with_seqs(&[3, 4, 5])).is_empty());
+    assert_eq!(
+        find_event_sequence_gaps(3, &events_with_seqs(&[4, 5, 8])),
+        vec![3, 6, 7]
+    );
+    assert_eq!(
+        find_event_sequence_gaps(0, &events_with_seqs(&[2, 0])),
+        vec![1]
+    );
+}
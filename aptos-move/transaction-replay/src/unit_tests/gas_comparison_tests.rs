@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{unit_tests::TestInterface, AptosDebugger, GasComparison};
+
+#[test]
+fn test_compare_gas_usage_past_ledger_end() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    assert_eq!(
+        debugger.compare_gas_usage(0, 10, None).unwrap(),
+        vec![GasComparison {
+            version: 0,
+            committed_gas: 0,
+            recomputed_gas: 0,
+            delta: 0,
+        }]
+    );
+    assert!(debugger.compare_gas_usage(1, 10, None).unwrap().is_empty());
+}
@@ -0,0 +1,127 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    compile_move_script, unit_tests::TestInterface, AptosDebugger, GasProfile, GasProfileEntry,
+};
+use aptos_types::{
+    account_address::AccountAddress, account_config::aptos_root_address, transaction::Transaction,
+};
+use move_deps::{
+    move_core_types::gas_schedule::GasAlgebra, move_vm_types::gas_schedule::INITIAL_COST_SCHEDULE,
+};
+use std::path::PathBuf;
+
+#[test]
+fn test_write_folded_gas_profile() {
+    let mut profile = GasProfile::new();
+    profile.record(
+        vec!["txn_1".to_string(), "0x1::Coin::transfer".to_string()],
+        10,
+    );
+    profile.record(vec!["txn_1".to_string()], 3);
+    profile.record(
+        vec!["txn_1".to_string(), "0x1::Coin::transfer".to_string()],
+        5,
+    );
+    profile.record(vec!["txn_1".to_string(), "a;b c".to_string()], 1);
+    assert_eq!(profile.total_gas(), 19);
+
+    let mut out = vec![];
+    profile.write_folded(&mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "txn_1 3\ntxn_1;0x1::Coin::transfer 15\ntxn_1;a:b_c 1\n"
+    );
+}
+
+#[test]
+fn test_gas_profile_json_round_trip() {
+    let mut profile = GasProfile::new();
+    profile.record(
+        vec![
+            "txn_1".to_string(),
+            "script".to_string(),
+            "intrinsic".to_string(),
+        ],
+        7,
+    );
+    profile.record(
+        vec![
+            "txn_1".to_string(),
+            "script".to_string(),
+            "execution".to_string(),
+        ],
+        11,
+    );
+
+    let json = serde_json::to_value(&profile).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"stack": ["txn_1", "script", "execution"], "gas": 11},
+            {"stack": ["txn_1", "script", "intrinsic"], "gas": 7},
+        ])
+    );
+    assert_eq!(serde_json::from_value::<GasProfile>(json).unwrap(), profile);
+}
+
+fn run_account_exists_metered(debugger: &AptosDebugger, max_gas: u64) -> (bool, u64) {
+    let mut script_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    script_path.push("examples/account_exists.move");
+    let script = compile_move_script(script_path.to_str().unwrap()).unwrap();
+    let sender = AccountAddress::from_hex_literal("0xbeef").unwrap();
+
+    let mut gas_used = 0;
+    let result = debugger.run_metered_session_at_version(
+        1,
+        None,
+        Some(&*INITIAL_COST_SCHEDULE),
+        max_gas,
+        |session, gas_status| {
+            let result = session.execute_script(
+                script,
+                vec![],
+                vec![aptos_root_address().to_vec(), sender.to_vec()],
+                gas_status,
+            );
+            gas_used = max_gas - gas_status.remaining_gas().get();
+            result
+        },
+    );
+    (result.is_ok(), gas_used)
+}
+
+#[test]
+fn test_run_metered_session_at_version() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    let (succeeded, gas_used) = run_account_exists_metered(&debugger, 1_000_000);
+    assert!(succeeded);
+    assert!(gas_used > 0);
+
+    // The budget is enforced to the unit.
+    assert_eq!(
+        run_account_exists_metered(&debugger, gas_used),
+        (true, gas_used)
+    );
+    assert!(!run_account_exists_metered(&debugger, gas_used - 1).0);
+}
+
+#[test]
+fn test_profile_transaction_at_version() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    let profile = debugger
+        .profile_transaction_at_version(1, Transaction::StateCheckpoint)
+        .unwrap();
+    assert_eq!(
+        profile.entries().collect::<Vec<_>>(),
+        vec![GasProfileEntry {
+            stack: vec![
+                "txn_1".to_string(),
+                "state_checkpoint".to_string(),
+                "execution".to_string(),
+            ],
+            gas: 0,
+        }]
+    );
+}
@@ -1,17 +1,30 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod annotated_session_tests;
+mod async_tests;
 mod bisection_tests;
+mod checkpoint_tests;
+mod divergence_tests;
+mod event_tests;
+mod gas_comparison_tests;
+mod gas_profile_tests;
+mod module_override_tests;
+mod output_record_tests;
+mod voting_power_tests;
 
 use crate::AptosValidatorInterface;
-use anyhow::{bail, Result};
+use anyhow::Result;
 use aptos_types::{
     account_address::AccountAddress,
     account_state::AccountState,
     contract_event::EventWithVersion,
     event::EventKey,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{Transaction, Version, WriteSetPayload},
+    transaction::{
+        ExecutionStatus, Transaction, TransactionOutput, TransactionStatus, Version,
+        WriteSetPayload,
+    },
     write_set::WriteOp,
 };
 use std::collections::HashMap;
@@ -20,6 +33,7 @@ use vm_genesis::{generate_genesis_change_set_for_testing, GenesisOptions};
 pub struct TestInterface {
     state_db: HashMap<(Version, StateKey), StateValue>,
     transaction_store: Vec<Transaction>,
+    output_store: Vec<TransactionOutput>,
     latest_version: u64,
 }
 
@@ -33,6 +47,7 @@ impl TestInterface {
         Self {
             state_db,
             transaction_store,
+            output_store: vec![],
             latest_version,
         }
     }
@@ -41,6 +56,7 @@ impl TestInterface {
         Self {
             state_db: HashMap::new(),
             transaction_store: vec![],
+            output_store: vec![],
             latest_version: version,
         }
     }
@@ -54,11 +70,18 @@ impl TestInterface {
                 _ => panic!("Unexpected delete"),
             };
         }
+        let output = TransactionOutput::new(
+            changeset.write_set().clone(),
+            changeset.events().to_vec(),
+            0,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+        );
         Self {
             state_db,
             transaction_store: vec![Transaction::GenesisTransaction(WriteSetPayload::Direct(
                 changeset,
             ))],
+            output_store: vec![output],
             latest_version: 1,
         }
     }
@@ -81,15 +104,29 @@ impl AptosValidatorInterface for TestInterface {
         Ok(self.state_db.get(&(version, state_key.clone())).cloned())
     }
 
+    // Like the DB, ranges running past the end of the store are cut short.
     fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
-        if start + limit >= self.transaction_store.len() as u64 {
-            bail!("Unexpected length")
-        }
-        let mut result = vec![];
-        for i in start..start + limit {
-            result.push(self.transaction_store[i as usize].clone())
-        }
-        Ok(result)
+        Ok(self
+            .transaction_store
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn get_committed_transaction_outputs(
+        &self,
+        start: Version,
+        limit: u64,
+    ) -> Result<Vec<TransactionOutput>> {
+        Ok(self
+            .output_store
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
     }
 
     fn get_latest_version(&self) -> Result<Version> {
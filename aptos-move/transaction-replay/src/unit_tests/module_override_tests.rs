@@ -0,0 +1,117 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{unit_tests::TestInterface, AptosDebugger, ReplayError};
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{aptos_root_address, AccountResource},
+    chain_id::ChainId,
+    state_store::state_key::StateKey,
+    transaction::{
+        ExecutionStatus, RawTransaction, ScriptFunction, Transaction, TransactionStatus,
+    },
+};
+use aptos_validator_interface::DebuggerStateView;
+use move_deps::{
+    move_binary_format::file_format::{
+        CompiledModule, FieldDefinition, SignatureToken, StructFieldInformation, TypeSignature,
+    },
+    move_core_types::{
+        identifier::Identifier, language_storage::ModuleId, move_resource::MoveStructType,
+    },
+};
+use vm_genesis::GENESIS_KEYPAIR;
+
+fn account_module() -> (ModuleId, CompiledModule) {
+    let tag = AccountResource::struct_tag();
+    let module_id = ModuleId::new(tag.address, tag.module);
+    let interface = TestInterface::genesis();
+    let bytes = DebuggerStateView::new(&interface, Some(0))
+        .get_state_value(&StateKey::AccessPath(AccessPath::code_access_path(
+            module_id.clone(),
+        )))
+        .unwrap()
+        .unwrap();
+    (module_id, CompiledModule::deserialize(&bytes).unwrap())
+}
+
+fn serialize(module: &CompiledModule) -> Vec<u8> {
+    let mut bytes = vec![];
+    module.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// A transaction from the root account creating a new account, which runs the prologue and
+/// `create_account` of the Account module.
+fn create_account_transaction() -> Transaction {
+    let (module_id, _) = account_module();
+    let new_account = AccountAddress::from_hex_literal("0xbeef").unwrap();
+    let txn = RawTransaction::new_script_function(
+        aptos_root_address(),
+        0,
+        ScriptFunction::new(
+            module_id,
+            Identifier::new("create_account").unwrap(),
+            vec![],
+            vec![bcs::to_bytes(&new_account).unwrap()],
+        ),
+        100_000,
+        0,
+        u64::MAX,
+        ChainId::test(),
+    )
+    .sign(&GENESIS_KEYPAIR.0, GENESIS_KEYPAIR.1.clone())
+    .unwrap()
+    .into_inner();
+    Transaction::UserTransaction(txn)
+}
+
+#[test]
+fn test_module_override_keeping_layout() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    let (module_id, module) = account_module();
+    let output = debugger
+        .execute_transaction_at_version_with_module_override(
+            1,
+            create_account_transaction(),
+            vec![(module_id, serialize(&module))],
+        )
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+}
+
+#[test]
+fn test_module_override_changing_layout() {
+    let debugger = AptosDebugger::new(Box::new(TestInterface::genesis()));
+    let (module_id, mut module) = account_module();
+    let fields = module
+        .struct_defs
+        .iter_mut()
+        .find_map(|def| match &mut def.field_information {
+            StructFieldInformation::Declared(fields) if !fields.is_empty() => Some(fields),
+            _ => None,
+        })
+        .unwrap();
+    let name = fields[0].name;
+    fields.push(FieldDefinition {
+        name,
+        signature: TypeSignature(SignatureToken::U64),
+    });
+
+    let err = debugger
+        .execute_transaction_at_version_with_module_override(
+            1,
+            create_account_transaction(),
+            vec![(module_id.clone(), serialize(&module))],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ReplayError>(),
+        Some(ReplayError::IncompatibleModuleOverride { module_id: id }) if *id == module_id
+    ));
+}
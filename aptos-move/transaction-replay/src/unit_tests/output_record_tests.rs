@@ -0,0 +1,44 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ReplayOutputRecord;
+use aptos_types::{
+    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    vm_status::AbortLocation,
+    write_set::WriteSet,
+};
+use serde_json::json;
+
+fn record_with_status(status: TransactionStatus) -> ReplayOutputRecord {
+    let output = TransactionOutput::new(WriteSet::default(), vec![], 5, status);
+    ReplayOutputRecord::new(3, &output).unwrap()
+}
+
+#[test]
+fn test_replay_output_record_status() {
+    let record = record_with_status(TransactionStatus::Keep(ExecutionStatus::Success));
+    let json = serde_json::to_value(&record).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "version": 3,
+            "gas_used": 5,
+            "status": {"Keep": "Success"},
+            "write_set": [],
+            "events": [],
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<ReplayOutputRecord>(json).unwrap(),
+        record
+    );
+
+    let record = record_with_status(TransactionStatus::Keep(ExecutionStatus::MoveAbort {
+        location: AbortLocation::Script,
+        code: 7,
+    }));
+    assert_eq!(
+        serde_json::to_value(&record).unwrap()["status"],
+        json!({"Keep": {"MoveAbort": {"location": "Script", "code": 7}}})
+    );
+}
@@ -0,0 +1,31 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::VotingPowerDistribution;
+
+#[test]
+fn test_equal_voting_power() {
+    let distribution = VotingPowerDistribution::from_voting_powers(vec![10; 10]);
+    assert_eq!(distribution.num_validators, 10);
+    assert_eq!(distribution.total_voting_power, 100);
+    // 4 validators hold 40% > 1/3 of the voting power.
+    assert_eq!(distribution.nakamoto_coefficient, 4);
+    assert!((distribution.top_k_shares[&1] - 0.1).abs() < f64::EPSILON);
+    assert!((distribution.top_k_shares[&10] - 1.0).abs() < f64::EPSILON);
+    assert!(distribution.gini.abs() < 1e-9);
+}
+
+#[test]
+fn test_concentrated_voting_power() {
+    let distribution = VotingPowerDistribution::from_voting_powers(vec![0, 0, 0, 100]);
+    assert_eq!(distribution.nakamoto_coefficient, 1);
+    assert!((distribution.top_k_shares[&1] - 1.0).abs() < f64::EPSILON);
+    assert!((distribution.gini - 0.75).abs() < 1e-9);
+}
+
+#[test]
+fn test_empty_validator_set() {
+    let distribution = VotingPowerDistribution::from_voting_powers(vec![]);
+    assert_eq!(distribution.nakamoto_coefficient, 0);
+    assert!(distribution.gini.abs() < f64::EPSILON);
+}
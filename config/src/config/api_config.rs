@@ -18,6 +18,10 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    // Origins allowed to make cross-origin requests, e.g. "https://example.com".
+    // If empty, any origin is allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cors_allowed_origins: Vec<String>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
@@ -38,6 +42,7 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            cors_allowed_origins: vec![],
         }
     }
 }
@@ -568,7 +568,7 @@ impl EpochManager {
         };
         self.shutdown_current_processor().await;
 
-        let onchain_config: anyhow::Result<OnChainConsensusConfig> = payload.get();
+        let onchain_config = payload.get::<OnChainConsensusConfig>();
         if let Err(error) = &onchain_config {
             error!("Failed to read on-chain consensus config {}", error);
         }
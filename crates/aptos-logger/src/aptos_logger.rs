@@ -7,26 +7,35 @@
 use crate::{
     counters::{
         PROCESSED_STRUCT_LOG_COUNT, SENT_STRUCT_LOG_BYTES, SENT_STRUCT_LOG_COUNT,
-        STRUCT_LOG_PARSE_ERROR_COUNT, STRUCT_LOG_QUEUE_ERROR_COUNT, STRUCT_LOG_SEND_ERROR_COUNT,
+        SENT_STRUCT_LOG_WIRE_BYTES, STRUCT_LOG_ENDPOINT_SEND_ERROR_COUNT,
+        STRUCT_LOG_ENDPOINT_SEND_RETRY_COUNT, STRUCT_LOG_FORMAT_ERROR_COUNT,
+        STRUCT_LOG_KEY_SAMPLED_COUNT, STRUCT_LOG_PARSE_ERROR_COUNT, STRUCT_LOG_QUEUE_CAPACITY,
+        STRUCT_LOG_QUEUE_DEPTH, STRUCT_LOG_QUEUE_ERROR_COUNT, STRUCT_LOG_RATE_LIMITED_COUNT,
+        STRUCT_LOG_SEND_ERROR_COUNT,
     },
     logger::Logger,
-    struct_log::TcpWriter,
+    queue::BoundedQueue,
+    struct_log::{TcpWriter, TlsOptions, DEFAULT_INITIAL_BACKOFF, DEFAULT_MAX_BACKOFF},
     Event, Filter, Key, Level, LevelFilter, Metadata,
 };
-use aptos_infallible::RwLock;
+use aptos_infallible::{Mutex, RwLock};
 use backtrace::Backtrace;
-use chrono::{SecondsFormat, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
+use flate2::{write::GzEncoder, Compression};
 use once_cell::sync::Lazy;
 use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    convert::TryFrom,
     env, fmt,
-    io::Write,
+    io::{self, Write},
     sync::{
-        mpsc::{self, Receiver, SyncSender},
+        mpsc::{self, RecvTimeoutError, SyncSender},
         Arc,
     },
-    thread,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime},
 };
 
 const RUST_LOG: &str = "RUST_LOG";
@@ -34,6 +43,14 @@ const RUST_LOG_REMOTE: &str = "RUST_LOG_REMOTE";
 /// Default size of log write channel, if the channel is full, logs will be dropped
 pub const CHANNEL_SIZE: usize = 10000;
 const NUM_SEND_RETRIES: u8 = 1;
+/// How long `AptosData::shutdown` waits for the queued entries to be written
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// The value logged in place of redacted fields
+const REDACTED: &str = "[REDACTED]";
+/// Default number of remote log lines written to the endpoints at once
+const DEFAULT_REMOTE_BATCH_SIZE: usize = 64;
+/// Default time the first line of a batch waits for the batch to fill up before it's sent anyway
+const DEFAULT_REMOTE_BATCH_INTERVAL: Duration = Duration::from_millis(100);
 
 /// A single log entry emitted by a logging macro with associated metadata
 #[derive(Debug, Serialize)]
@@ -56,7 +73,13 @@ pub struct LogEntry {
 }
 
 impl LogEntry {
-    fn new(event: &Event, thread_name: Option<&str>, enable_backtrace: bool) -> Self {
+    fn new(
+        event: &Event,
+        thread_name: Option<&str>,
+        backtrace_level: Option<Level>,
+        include_hostname: bool,
+        timestamp_format: TimestampFormat,
+    ) -> Self {
         use crate::{Value, Visitor};
 
         struct JsonVisitor<'a>(&'a mut BTreeMap<Key, serde_json::Value>);
@@ -89,9 +112,13 @@ impl LogEntry {
                 .and_then(|name| name.into_string().ok())
         });
 
-        let hostname = HOSTNAME.as_deref();
+        let hostname = if include_hostname {
+            HOSTNAME.as_deref()
+        } else {
+            None
+        };
 
-        let backtrace = if enable_backtrace && matches!(metadata.level(), Level::Error) {
+        let backtrace = if backtrace_level.map_or(false, |level| metadata.level() <= level) {
             let mut backtrace = Backtrace::new();
             let mut frames = backtrace.frames().to_vec();
             if frames.len() > 3 {
@@ -113,7 +140,7 @@ impl LogEntry {
             thread_name,
             backtrace,
             hostname,
-            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+            timestamp: timestamp_format.format(Utc::now()),
             data,
             message,
         }
@@ -143,21 +170,122 @@ impl LogEntry {
         &self.data
     }
 
+    /// Adds `fields` to the data of the entry, keeping the value set at the call site if a key is
+    /// present in both
+    fn add_global_fields(&mut self, fields: &BTreeMap<Key, serde_json::Value>) {
+        for (key, value) in fields {
+            self.data
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+
+    /// Replaces the values of `keys` in the data of the entry with a placeholder
+    fn redact(&mut self, keys: &BTreeSet<Key>) {
+        for key in keys {
+            if let Some(value) = self.data.get_mut(key) {
+                *value = serde_json::Value::from(REDACTED);
+            }
+        }
+    }
+
     pub fn message(&self) -> Option<&str> {
         self.message.as_deref()
     }
 }
 
+/// How the timestamp of a `LogEntry` is written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// RFC 3339 in UTC with microseconds, e.g. `2020-03-07T05:03:03.123456Z`
+    Rfc3339Micros,
+    /// RFC 3339 in UTC with nanoseconds, e.g. `2020-03-07T05:03:03.123456789Z`
+    Rfc3339Nanos,
+    /// Milliseconds since the Unix epoch
+    UnixMillis,
+    /// Nanoseconds since the Unix epoch
+    UnixNanos,
+}
+
+impl Default for TimestampFormat {
+    fn default() -> Self {
+        TimestampFormat::Rfc3339Micros
+    }
+}
+
+impl TimestampFormat {
+    fn format(self, time: DateTime<Utc>) -> String {
+        match self {
+            TimestampFormat::Rfc3339Micros => time.to_rfc3339_opts(SecondsFormat::Micros, true),
+            TimestampFormat::Rfc3339Nanos => time.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            TimestampFormat::UnixMillis => time.timestamp_millis().to_string(),
+            TimestampFormat::UnixNanos => time.timestamp_nanos().to_string(),
+        }
+    }
+}
+
+/// How batches of remote logs are encoded before being written to the endpoints
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteCompression {
+    /// Newline delimited JSON, as is
+    None,
+    /// Each batch of newline delimited JSON is gzipped, and written as a 4 byte big endian length
+    /// followed by that many bytes of gzip data
+    Gzip,
+}
+
+impl Default for RemoteCompression {
+    fn default() -> Self {
+        RemoteCompression::None
+    }
+}
+
+impl RemoteCompression {
+    /// The bytes to write for `batch`
+    fn encode(self, batch: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+        match self {
+            RemoteCompression::None => Ok(Cow::Borrowed(batch)),
+            RemoteCompression::Gzip => {
+                // Reserve the length prefix, filled in once the compressed size is known
+                let mut encoder = GzEncoder::new(vec![0; 4], Compression::default());
+                encoder.write_all(batch)?;
+                let mut frame = encoder.finish()?;
+                let length = u32::try_from(frame.len() - 4)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                frame[..4].copy_from_slice(&length.to_be_bytes());
+                Ok(Cow::Owned(frame))
+            }
+        }
+    }
+}
+
 /// A builder for a `AptosData`, configures what, where, and how to write logs.
 pub struct AptosDataBuilder {
     channel_size: usize,
+    max_channel_size: Option<usize>,
     enable_backtrace: bool,
+    backtrace_level: Level,
+    include_hostname: bool,
+    timestamp_format: TimestampFormat,
     level: Level,
     remote_level: Level,
-    address: Option<String>,
+    local_filter: Option<Filter>,
+    remote_filter: Option<Filter>,
+    remote_target_levels: Vec<(String, LevelFilter)>,
+    addresses: Vec<String>,
+    tls: Option<TlsOptions>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    remote_batch_size: usize,
+    remote_batch_interval: Duration,
+    remote_compression: RemoteCompression,
     printer: Option<Box<dyn Writer>>,
     is_async: bool,
     custom_format: Option<fn(&LogEntry) -> Result<String, fmt::Error>>,
+    max_logs_per_second_per_call_site: Option<u64>,
+    sample_by_key: Option<(Key, Duration)>,
+    global_fields: BTreeMap<Key, serde_json::Value>,
+    redacted_keys: BTreeSet<Key>,
 }
 
 impl AptosDataBuilder {
@@ -165,18 +293,70 @@ impl AptosDataBuilder {
     pub fn new() -> Self {
         Self {
             channel_size: CHANNEL_SIZE,
+            max_channel_size: None,
             enable_backtrace: false,
+            backtrace_level: Level::Error,
+            include_hostname: true,
+            timestamp_format: TimestampFormat::default(),
             level: Level::Info,
             remote_level: Level::Info,
-            address: None,
+            local_filter: None,
+            remote_filter: None,
+            remote_target_levels: Vec::new(),
+            addresses: Vec::new(),
+            tls: None,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            remote_batch_size: DEFAULT_REMOTE_BATCH_SIZE,
+            remote_batch_interval: DEFAULT_REMOTE_BATCH_INTERVAL,
+            remote_compression: RemoteCompression::default(),
             printer: Some(Box::new(StderrWriter)),
             is_async: false,
             custom_format: None,
+            max_logs_per_second_per_call_site: None,
+            sample_by_key: None,
+            global_fields: BTreeMap::new(),
+            redacted_keys: BTreeSet::new(),
         }
     }
 
+    /// Send remote logs to `address` only, replacing any previously added addresses
     pub fn address(&mut self, address: String) -> &mut Self {
-        self.address = Some(address);
+        self.addresses = vec![address];
+        self
+    }
+
+    /// Send remote logs to `address` in addition to the addresses already configured
+    pub fn add_address(&mut self, address: String) -> &mut Self {
+        self.addresses.push(address);
+        self
+    }
+
+    /// Send remote logs over TLS instead of plaintext TCP
+    pub fn tls(&mut self, options: TlsOptions) -> &mut Self {
+        self.tls = Some(options);
+        self
+    }
+
+    /// Wait `initial` before retrying a failed send to a remote endpoint, doubling the delay on
+    /// every retry up to `max`. The delay is reset once the connection is re-established.
+    pub fn remote_backoff(&mut self, initial: Duration, max: Duration) -> &mut Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Write remote logs in batches of up to `size` lines, sending a partial batch once its first
+    /// line has waited for `interval`. `flush` always sends the pending lines right away.
+    pub fn remote_batch(&mut self, size: usize, interval: Duration) -> &mut Self {
+        self.remote_batch_size = size.max(1);
+        self.remote_batch_interval = interval;
+        self
+    }
+
+    /// Compress every batch of remote logs, see `RemoteCompression` for the framing
+    pub fn remote_compression(&mut self, compression: RemoteCompression) -> &mut Self {
+        self.remote_compression = compression;
         self
     }
 
@@ -185,6 +365,25 @@ impl AptosDataBuilder {
         self
     }
 
+    /// Capture backtraces for entries of `level` and above when backtraces are enabled, defaults
+    /// to `Level::Error`
+    pub fn backtrace_level(&mut self, level: Level) -> &mut Self {
+        self.backtrace_level = level;
+        self
+    }
+
+    /// Whether to look up the hostname and include it in every log entry, defaults to true
+    pub fn include_hostname(&mut self, include_hostname: bool) -> &mut Self {
+        self.include_hostname = include_hostname;
+        self
+    }
+
+    /// How to write the timestamp of log entries, defaults to `TimestampFormat::Rfc3339Micros`
+    pub fn timestamp_format(&mut self, timestamp_format: TimestampFormat) -> &mut Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+
     pub fn read_env(&mut self) -> &mut Self {
         if let Ok(address) = env::var("STRUCT_LOG_TCP_ADDR") {
             self.address(address);
@@ -202,11 +401,39 @@ impl AptosDataBuilder {
         self
     }
 
+    /// Send the logs of `target` and its submodules remotely at most at `level`, taking
+    /// precedence over `remote_level`, `RUST_LOG_REMOTE` and `RUST_LOG`. Has no effect on the
+    /// local filter, nor if a `remote_filter` is set.
+    pub fn remote_target_level(&mut self, target: &str, level: LevelFilter) -> &mut Self {
+        self.remote_target_levels.push((target.to_string(), level));
+        self
+    }
+
+    /// Use `filter` as the local filter, taking precedence over `level` and `RUST_LOG`
+    pub fn local_filter(&mut self, filter: Filter) -> &mut Self {
+        self.local_filter = Some(filter);
+        self
+    }
+
+    /// Use `filter` as the remote filter, taking precedence over `remote_level`,
+    /// `RUST_LOG_REMOTE` and `RUST_LOG`
+    pub fn remote_filter(&mut self, filter: Filter) -> &mut Self {
+        self.remote_filter = Some(filter);
+        self
+    }
+
     pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
         self.channel_size = channel_size;
         self
     }
 
+    /// The size the channel can be grown to with `AptosData::set_channel_size`, defaults to
+    /// `channel_size`
+    pub fn max_channel_size(&mut self, max_channel_size: usize) -> &mut Self {
+        self.max_channel_size = Some(max_channel_size);
+        self
+    }
+
     pub fn printer(&mut self, printer: Box<dyn Writer + Send + Sync + 'static>) -> &mut Self {
         self.printer = Some(printer);
         self
@@ -225,13 +452,54 @@ impl AptosDataBuilder {
         self
     }
 
+    /// Emit at most `max_logs` entries per second from each logging call site (file and line),
+    /// dropping the rest
+    pub fn max_logs_per_second_per_call_site(&mut self, max_logs: u64) -> &mut Self {
+        self.max_logs_per_second_per_call_site = Some(max_logs);
+        self
+    }
+
+    /// Emit at most one entry per `interval` for each value of the field `key`, e.g. one per
+    /// second per account address. Entries without the field aren't sampled.
+    pub fn sample_by_key(&mut self, key: &'static str, interval: Duration) -> &mut Self {
+        self.sample_by_key = Some((Key::new(key), interval));
+        self
+    }
+
+    /// Add `key = value` to the data of every log entry, e.g. to tag all logs with the chain id.
+    /// The value is serialized once, here.
+    pub fn global_field<V: Serialize>(&mut self, key: &'static str, value: V) -> &mut Self {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                self.global_fields.insert(Key::new(key), value);
+            }
+            Err(e) => eprintln!("error serializing global log field {}: {}", key, e),
+        }
+        self
+    }
+
+    /// Replace the value of `key` with a placeholder in every log entry, locally and remotely
+    pub fn redact_key(&mut self, key: &'static str) -> &mut Self {
+        self.redacted_keys.insert(Key::new(key));
+        self
+    }
+
     pub fn init(&mut self) {
         self.build();
     }
 
     pub fn build(&mut self) -> Arc<AptosData> {
+        let logger = self.build_logger();
+        crate::logger::set_global_logger(logger.clone());
+        logger
+    }
+
+    /// Same as `build`, without installing the logger as the global one
+    fn build_logger(&mut self) -> Arc<AptosData> {
         let filter = {
-            let local_filter = {
+            let local_filter = if let Some(filter) = self.local_filter.take() {
+                filter
+            } else {
                 let mut filter_builder = Filter::builder();
 
                 if env::var(RUST_LOG).is_ok() {
@@ -242,10 +510,12 @@ impl AptosDataBuilder {
 
                 filter_builder.build()
             };
-            let remote_filter = {
+            let remote_filter = if let Some(filter) = self.remote_filter.take() {
+                filter
+            } else {
                 let mut filter_builder = Filter::builder();
 
-                if self.is_async && self.address.is_some() {
+                if self.is_async && !self.addresses.is_empty() {
                     if env::var(RUST_LOG_REMOTE).is_ok() {
                         filter_builder.with_env(RUST_LOG_REMOTE);
                     } else if env::var(RUST_LOG).is_ok() {
@@ -253,6 +523,9 @@ impl AptosDataBuilder {
                     } else {
                         filter_builder.filter_level(self.remote_level.into());
                     }
+                    for (target, level) in &self.remote_target_levels {
+                        filter_builder.filter_module(target, *level);
+                    }
                 } else {
                     filter_builder.filter_level(LevelFilter::Off);
                 }
@@ -267,34 +540,61 @@ impl AptosDataBuilder {
         };
 
         let logger = if self.is_async {
-            let (sender, receiver) = mpsc::sync_channel(self.channel_size);
+            let queue = Arc::new(BoundedQueue::new(
+                self.channel_size,
+                self.max_channel_size.unwrap_or(self.channel_size),
+            ));
+            STRUCT_LOG_QUEUE_CAPACITY.set(queue.capacity() as i64);
             let logger = Arc::new(AptosData {
-                enable_backtrace: self.enable_backtrace,
-                sender: Some(sender),
+                backtrace_level: self.enable_backtrace.then(|| self.backtrace_level),
+                include_hostname: self.include_hostname,
+                timestamp_format: self.timestamp_format,
+                sender: Some(queue.clone()),
+                service: Mutex::new(None),
                 printer: None,
                 filter: RwLock::new(filter),
                 formatter: self.custom_format.take().unwrap_or(default_format),
+                rate_limiter: self
+                    .max_logs_per_second_per_call_site
+                    .map(CallSiteRateLimiter::new),
+                key_sampler: self.sample_by_key.clone().map(KeySampler::new),
+                global_fields: self.global_fields.clone(),
+                redacted_keys: self.redacted_keys.clone(),
             });
             let service = LoggerService {
-                receiver,
-                address: self.address.clone(),
+                receiver: queue,
+                addresses: self.addresses.clone(),
+                tls: self.tls.clone(),
+                initial_backoff: self.initial_backoff,
+                max_backoff: self.max_backoff,
+                batch_size: self.remote_batch_size,
+                batch_interval: self.remote_batch_interval,
+                compression: self.remote_compression,
                 printer: self.printer.take(),
                 facade: logger.clone(),
             };
 
-            thread::spawn(move || service.run());
+            *logger.service.lock() = Some(thread::spawn(move || service.run()));
             logger
         } else {
             Arc::new(AptosData {
-                enable_backtrace: self.enable_backtrace,
+                backtrace_level: self.enable_backtrace.then(|| self.backtrace_level),
+                include_hostname: self.include_hostname,
+                timestamp_format: self.timestamp_format,
                 sender: None,
+                service: Mutex::new(None),
                 printer: self.printer.take(),
                 filter: RwLock::new(filter),
                 formatter: self.custom_format.take().unwrap_or(default_format),
+                rate_limiter: self
+                    .max_logs_per_second_per_call_site
+                    .map(CallSiteRateLimiter::new),
+                key_sampler: self.sample_by_key.clone().map(KeySampler::new),
+                global_fields: self.global_fields.clone(),
+                redacted_keys: self.redacted_keys.clone(),
             })
         };
 
-        crate::logger::set_global_logger(logger.clone());
         logger
     }
 }
@@ -314,11 +614,20 @@ impl FilterPair {
 }
 
 pub struct AptosData {
-    enable_backtrace: bool,
-    sender: Option<SyncSender<LoggerServiceEvent>>,
+    /// The least severe level for which backtraces are captured, `None` if disabled
+    backtrace_level: Option<Level>,
+    include_hostname: bool,
+    timestamp_format: TimestampFormat,
+    sender: Option<Arc<BoundedQueue<LoggerServiceEvent>>>,
+    /// The thread of the `LoggerService`, until `shutdown` joins it
+    service: Mutex<Option<JoinHandle<()>>>,
     printer: Option<Box<dyn Writer>>,
     filter: RwLock<FilterPair>,
     pub(crate) formatter: fn(&LogEntry) -> Result<String, fmt::Error>,
+    rate_limiter: Option<CallSiteRateLimiter>,
+    key_sampler: Option<KeySampler>,
+    global_fields: BTreeMap<Key, serde_json::Value>,
+    redacted_keys: BTreeSet<Key>,
 }
 
 impl AptosData {
@@ -351,17 +660,80 @@ impl AptosData {
         self.filter.write().remote_filter = filter;
     }
 
-    fn send_entry(&self, entry: LogEntry) {
+    /// Log `target` and its submodules locally at most at `level`, on top of the local filter
+    pub fn set_target_level(&self, target: &str, level: LevelFilter) {
+        self.filter.write().local_filter.set_override(target, level);
+    }
+
+    /// Restore the level set by the local filter for `target`
+    pub fn clear_target_level(&self, target: &str) {
+        self.filter.write().local_filter.clear_override(target);
+    }
+
+    /// Writes the entries still queued and stops the `LoggerService`, waiting up to
+    /// `SHUTDOWN_TIMEOUT` for it. Returns whether all entries were written in time. Entries logged
+    /// afterwards are dropped.
+    pub fn shutdown(&self) -> bool {
+        let sender = match &self.sender {
+            Some(sender) => sender,
+            None => return true,
+        };
+        let service = match self.service.lock().take() {
+            Some(service) => service,
+            None => return true,
+        };
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        let (oneshot_sender, oneshot_receiver) = mpsc::sync_channel(1);
+        STRUCT_LOG_QUEUE_DEPTH.inc();
+        if sender
+            .send_timeout(
+                LoggerServiceEvent::Shutdown(oneshot_sender),
+                SHUTDOWN_TIMEOUT,
+            )
+            .is_err()
+        {
+            STRUCT_LOG_QUEUE_DEPTH.dec();
+            eprintln!("[Logging] Failed to queue the shutdown of the logger service");
+            return false;
+        }
+        // The service handles events in order, so once it acknowledges the shutdown every entry
+        // queued before has been written
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if oneshot_receiver.recv_timeout(remaining).is_err() {
+            eprintln!("[Logging] Timed out waiting for the queued logs to be written");
+            return false;
+        }
+        service.join().is_ok()
+    }
+
+    /// Resizes the channel to the `LoggerService`, up to the `max_channel_size` it was built
+    /// with. Returns the new size, or `None` if the logger isn't async.
+    pub fn set_channel_size(&self, channel_size: usize) -> Option<usize> {
+        self.sender.as_ref().map(|sender| {
+            let channel_size = sender.set_capacity(channel_size);
+            STRUCT_LOG_QUEUE_CAPACITY.set(channel_size as i64);
+            channel_size
+        })
+    }
+
+    fn send_entry(&self, mut entry: LogEntry) {
+        entry.redact(&self.redacted_keys);
+
         if let Some(printer) = &self.printer {
-            let s = (self.formatter)(&entry).expect("Unable to format");
-            printer.write(s);
+            if let Some(s) = format_local(self.formatter, &entry) {
+                printer.write_with_level(entry.metadata.level(), s);
+            }
         }
 
         if let Some(sender) = &self.sender {
+            // Increment before sending so the service never observes a negative depth
+            STRUCT_LOG_QUEUE_DEPTH.inc();
             if sender
                 .try_send(LoggerServiceEvent::LogEntry(entry))
                 .is_err()
             {
+                STRUCT_LOG_QUEUE_DEPTH.dec();
                 STRUCT_LOG_QUEUE_ERROR_COUNT.inc();
             }
         }
@@ -374,45 +746,198 @@ impl Logger for AptosData {
     }
 
     fn record(&self, event: &Event) {
-        let entry = LogEntry::new(
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.allow(event.metadata()) {
+                STRUCT_LOG_RATE_LIMITED_COUNT.inc();
+                return;
+            }
+        }
+
+        let mut entry = LogEntry::new(
             event,
             ::std::thread::current().name(),
-            self.enable_backtrace,
+            self.backtrace_level,
+            self.include_hostname,
+            self.timestamp_format,
         );
+        if let Some(key_sampler) = &self.key_sampler {
+            if !key_sampler.allow(&entry.data, Instant::now()) {
+                STRUCT_LOG_KEY_SAMPLED_COUNT.inc();
+                return;
+            }
+        }
+        entry.add_global_fields(&self.global_fields);
 
         self.send_entry(entry)
     }
 
     fn flush(&self) {
+        if let Some(printer) = &self.printer {
+            printer.flush();
+        }
+
         if let Some(sender) = &self.sender {
             let (oneshot_sender, oneshot_receiver) = mpsc::sync_channel(1);
-            sender
+            STRUCT_LOG_QUEUE_DEPTH.inc();
+            // The service is gone after a shutdown, there's nothing left to flush
+            if sender
                 .send(LoggerServiceEvent::Flush(oneshot_sender))
-                .unwrap();
+                .is_err()
+            {
+                STRUCT_LOG_QUEUE_DEPTH.dec();
+                return;
+            }
             oneshot_receiver.recv().unwrap();
         }
     }
 }
 
+/// Limits the number of log entries emitted per second by each call site, identified by the
+/// file and line of the logging macro.
+struct CallSiteRateLimiter {
+    max_per_second: u64,
+    /// The current second and the number of entries emitted in it, per call site
+    windows: Mutex<HashMap<(&'static str, u32), (u64, u64)>>,
+}
+
+impl CallSiteRateLimiter {
+    fn new(max_per_second: u64) -> Self {
+        Self {
+            max_per_second,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn allow(&self, metadata: &Metadata) -> bool {
+        // Seconds since Unix Epoch
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("SystemTime before UNIX EPOCH!")
+            .as_secs();
+        self.allow_at(metadata, now)
+    }
+
+    fn allow_at(&self, metadata: &Metadata, now: u64) -> bool {
+        let mut windows = self.windows.lock();
+        let (second, count) = windows
+            .entry((metadata.file(), metadata.line()))
+            .or_insert((now, 0));
+        if *second != now {
+            *second = now;
+            *count = 0;
+        }
+        if *count < self.max_per_second {
+            *count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Samples entries on the value of a structured field, letting one entry through per `interval`
+/// for each value.
+struct KeySampler {
+    key: Key,
+    interval: Duration,
+    /// When an entry was last let through, per value of the field
+    last_emitted: Mutex<HashMap<String, Instant>>,
+}
+
+impl KeySampler {
+    /// The number of tracked values above which the expired ones are pruned
+    const PRUNE_THRESHOLD: usize = 1024;
+
+    fn new((key, interval): (Key, Duration)) -> Self {
+        Self {
+            key,
+            interval,
+            last_emitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn allow(&self, data: &BTreeMap<Key, serde_json::Value>, now: Instant) -> bool {
+        let value = match data.get(&self.key) {
+            Some(value) => value.to_string(),
+            None => return true,
+        };
+
+        let mut last_emitted = self.last_emitted.lock();
+        if last_emitted.len() >= Self::PRUNE_THRESHOLD {
+            let interval = self.interval;
+            last_emitted.retain(|_, emitted| now.saturating_duration_since(*emitted) < interval);
+        }
+        match last_emitted.get(&value) {
+            Some(emitted) if now.saturating_duration_since(*emitted) < self.interval => false,
+            _ => {
+                last_emitted.insert(value, now);
+                true
+            }
+        }
+    }
+}
+
 enum LoggerServiceEvent {
     LogEntry(LogEntry),
     Flush(SyncSender<()>),
+    /// Write everything queued so far, acknowledge and stop
+    Shutdown(SyncSender<()>),
 }
 
 /// A service for running a log listener, that will continually export logs through a local printer
 /// or to a `AptosData` for external logging.
 struct LoggerService {
-    receiver: Receiver<LoggerServiceEvent>,
-    address: Option<String>,
+    receiver: Arc<BoundedQueue<LoggerServiceEvent>>,
+    addresses: Vec<String>,
+    tls: Option<TlsOptions>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    batch_size: usize,
+    batch_interval: Duration,
+    compression: RemoteCompression,
     printer: Option<Box<dyn Writer>>,
     facade: Arc<AptosData>,
 }
 
 impl LoggerService {
     pub fn run(mut self) {
-        let mut writer = self.address.take().map(TcpWriter::new);
+        let tls = self.tls.take();
+        let (initial_backoff, max_backoff) = (self.initial_backoff, self.max_backoff);
+        let mut writers: Vec<_> = self
+            .addresses
+            .drain(..)
+            .map(|address| {
+                let mut writer = match &tls {
+                    Some(options) => TcpWriter::with_tls(address, options),
+                    None => TcpWriter::new(address),
+                };
+                writer.set_backoff(initial_backoff, max_backoff);
+                writer
+            })
+            .collect();
+
+        let mut batch = RemoteBatch::default();
+        loop {
+            // Wait for the next event, but no longer than the pending batch may be held back
+            let event = match batch.started {
+                Some(started) => {
+                    let timeout = self.batch_interval.saturating_sub(started.elapsed());
+                    match self.receiver.recv_timeout(timeout) {
+                        Ok(event) => event,
+                        Err(RecvTimeoutError::Timeout) => {
+                            Self::flush_batch(&mut writers, &mut batch, self.compression);
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                None => match self.receiver.recv() {
+                    Some(event) => event,
+                    None => break,
+                },
+            };
 
-        for event in self.receiver {
+            STRUCT_LOG_QUEUE_DEPTH.dec();
             match event {
                 LoggerServiceEvent::LogEntry(entry) => {
                     PROCESSED_STRUCT_LOG_COUNT.inc();
@@ -425,80 +950,168 @@ impl LoggerService {
                             .local_filter
                             .enabled(&entry.metadata)
                         {
-                            let s = (self.facade.formatter)(&entry).expect("Unable to format");
-                            printer.write(s)
+                            if let Some(s) = format_local(self.facade.formatter, &entry) {
+                                printer.write_with_level(entry.metadata.level(), s)
+                            }
                         }
                     }
 
-                    if let Some(writer) = &mut writer {
-                        if self
+                    if !writers.is_empty()
+                        && self
                             .facade
                             .filter
                             .read()
                             .remote_filter
                             .enabled(&entry.metadata)
-                        {
-                            Self::write_to_logstash(writer, entry);
+                    {
+                        if let Some(message) = Self::format_for_logstash(entry) {
+                            batch.push(&message);
+                            if batch.num_lines >= self.batch_size {
+                                Self::flush_batch(&mut writers, &mut batch, self.compression);
+                            }
                         }
                     }
                 }
                 LoggerServiceEvent::Flush(sender) => {
+                    Self::flush_batch(&mut writers, &mut batch, self.compression);
+                    if let Some(printer) = &self.printer {
+                        printer.flush();
+                    }
                     // This is just to notify the other side, the logger doesn't actually care if
                     // the listener is still listening
                     let _ = sender.send(());
                 }
+                LoggerServiceEvent::Shutdown(sender) => {
+                    Self::flush_batch(&mut writers, &mut batch, self.compression);
+                    if let Some(printer) = &self.printer {
+                        printer.flush();
+                    }
+                    let _ = sender.send(());
+                    break;
+                }
             }
         }
+
+        // Entries logged from now on are dropped instead of piling up in the queue
+        self.receiver.close();
+        Self::flush_batch(&mut writers, &mut batch, self.compression);
     }
 
-    /// Writes a log line into json_lines logstash format, which has a newline at the end
-    fn write_to_logstash(stream: &mut TcpWriter, mut entry: LogEntry) {
-        // XXX Temporary hack to ensure that log lines don't show up empty in kibana when the
-        // "message" field isn't set.
-        if entry.message.is_none() {
-            entry.message = Some(serde_json::to_string(&entry.data).unwrap());
+    /// Writes the pending lines to every endpoint and empties the batch
+    fn flush_batch(
+        writers: &mut [TcpWriter],
+        batch: &mut RemoteBatch,
+        compression: RemoteCompression,
+    ) {
+        if batch.num_lines == 0 {
+            return;
+        }
+        match compression.encode(batch.buffer.as_bytes()) {
+            Ok(payload) => {
+                // Each writer retries and reports on its own, so a destination that is down
+                // doesn't hold back the others
+                for writer in writers {
+                    Self::write_to_logstash(
+                        writer,
+                        &payload,
+                        batch.num_lines as u64,
+                        batch.buffer.len(),
+                    );
+                }
+            }
+            Err(e) => {
+                STRUCT_LOG_SEND_ERROR_COUNT.inc();
+                eprintln!("[Logging] Error while compressing logs: {}", e);
+            }
         }
+        *batch = RemoteBatch::default();
+    }
 
-        let message = if let Ok(json) = serde_json::to_string(&entry) {
+    /// Formats a log line into json_lines logstash format, which has a newline at the end
+    fn format_for_logstash(entry: LogEntry) -> Option<String> {
+        let message = if let Ok(json) = json_format(&entry) {
             json
         } else {
             STRUCT_LOG_PARSE_ERROR_COUNT.inc();
-            return;
+            return None;
         };
 
-        let message = message + "\n";
-        let bytes = message.as_bytes();
-        let message_length = bytes.len();
+        Some(message + "\n")
+    }
 
-        // Attempt to write the log up to NUM_SEND_RETRIES + 1, and then drop it
+    /// Writes `num_lines` formatted log lines, `message_length` bytes before compression, to a
+    /// single logstash endpoint
+    fn write_to_logstash(
+        stream: &mut TcpWriter,
+        bytes: &[u8],
+        num_lines: u64,
+        message_length: usize,
+    ) {
+        // Attempt to write the log up to NUM_SEND_RETRIES + 1, backing off between attempts, and
+        // then drop it
         // Each `write_all` call will attempt to open a connection if one isn't open
         let mut result = stream.write_all(bytes);
         for _ in 0..NUM_SEND_RETRIES {
             if result.is_ok() {
                 break;
             } else {
+                STRUCT_LOG_ENDPOINT_SEND_RETRY_COUNT
+                    .with_label_values(&[stream.endpoint()])
+                    .inc();
+                thread::sleep(stream.backoff());
                 result = stream.write_all(bytes);
             }
         }
 
         if let Err(e) = result {
             STRUCT_LOG_SEND_ERROR_COUNT.inc();
+            STRUCT_LOG_ENDPOINT_SEND_ERROR_COUNT
+                .with_label_values(&[stream.endpoint()])
+                .inc();
             eprintln!(
                 "[Logging] Error while sending data to logstash({}): {}",
                 stream.endpoint(),
                 e
             );
         } else {
-            SENT_STRUCT_LOG_COUNT.inc();
+            SENT_STRUCT_LOG_COUNT.inc_by(num_lines);
             SENT_STRUCT_LOG_BYTES.inc_by(message_length as u64);
+            SENT_STRUCT_LOG_WIRE_BYTES.inc_by(bytes.len() as u64);
         }
     }
 }
 
+/// Remote log lines accumulated by the `LoggerService`, written as a single buffer
+#[derive(Default)]
+struct RemoteBatch {
+    buffer: String,
+    num_lines: usize,
+    /// When the first line of the batch was added
+    started: Option<Instant>,
+}
+
+impl RemoteBatch {
+    fn push(&mut self, line: &str) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+        self.buffer.push_str(line);
+        self.num_lines += 1;
+    }
+}
+
 /// An trait encapsulating the operations required for writing logs.
 pub trait Writer: Send + Sync {
     /// Write the log.
     fn write(&self, log: String);
+
+    /// Write the log of an entry at `level`, for writers that handle levels themselves.
+    fn write_with_level(&self, _level: Level, log: String) {
+        self.write(log)
+    }
+
+    /// Write out any buffered logs, called when the logger is flushed.
+    fn flush(&self) {}
 }
 
 /// A struct for writing logs to stderr
@@ -538,6 +1151,279 @@ impl Writer for FileWriter {
     }
 }
 
+/// A logger that keeps the last `capacity` lines in memory, e.g. for tests or to serve the recent
+/// logs of a node. Clones share the same buffer, so a clone can be kept to read the lines while
+/// the original is given to `AptosDataBuilder::printer`.
+#[derive(Clone)]
+pub struct RingBufferWriter {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl RingBufferWriter {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    /// The retained lines, oldest first
+    pub fn snapshot(&self) -> Vec<String> {
+        self.lines.lock().iter().cloned().collect()
+    }
+
+    /// Removes and returns the retained lines, oldest first
+    pub fn drain(&self) -> Vec<String> {
+        self.lines.lock().drain(..).collect()
+    }
+}
+
+impl Writer for RingBufferWriter {
+    /// Keep the line, evicting the oldest one if the buffer is full
+    fn write(&self, log: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut lines = self.lines.lock();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(log);
+    }
+}
+
+/// A logger that writes to a file without blocking on the disk: lines are appended to an
+/// in-memory buffer, which a background thread writes out every `flush_interval`, or as soon as it
+/// holds `max_buffer_bytes`.
+///
+/// On a crash, the lines logged during the last `flush_interval` may be lost. `flush` writes the
+/// buffer out right away.
+pub struct BufferedFileWriter {
+    inner: Arc<BufferedFile>,
+    max_buffer_bytes: usize,
+    /// Wakes up the background thread before the interval elapses, dropped to stop it
+    wakeup: SyncSender<()>,
+}
+
+struct BufferedFile {
+    file: Mutex<std::fs::File>,
+    buffer: Mutex<Vec<u8>>,
+}
+
+impl BufferedFile {
+    fn flush(&self) {
+        // Hold the file while taking the buffer, so concurrent flushes write in order
+        let mut file = self.file.lock();
+        let buffer = std::mem::take(&mut *self.buffer.lock());
+        if buffer.is_empty() {
+            return;
+        }
+        if let Err(err) = file.write_all(&buffer).and_then(|()| file.flush()) {
+            eprintln!("Unable to write to log file: {}", err);
+        }
+    }
+}
+
+impl BufferedFileWriter {
+    pub fn new(
+        log_file: std::path::PathBuf,
+        max_buffer_bytes: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(log_file)
+            .expect("Unable to open log file");
+        let inner = Arc::new(BufferedFile {
+            file: Mutex::new(file),
+            buffer: Mutex::new(Vec::with_capacity(max_buffer_bytes)),
+        });
+        let (wakeup, wakeups) = mpsc::sync_channel(1);
+
+        let background = inner.clone();
+        thread::spawn(move || loop {
+            match wakeups.recv_timeout(flush_interval) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => background.flush(),
+                Err(RecvTimeoutError::Disconnected) => {
+                    background.flush();
+                    break;
+                }
+            }
+        });
+
+        Self {
+            inner,
+            max_buffer_bytes,
+            wakeup,
+        }
+    }
+}
+
+impl Writer for BufferedFileWriter {
+    /// Append to the buffer, waking up the background thread if it's full
+    fn write(&self, log: String) {
+        let mut buffer = self.inner.buffer.lock();
+        buffer.extend_from_slice(log.as_bytes());
+        buffer.push(b'\n');
+        if buffer.len() >= self.max_buffer_bytes {
+            // A wakeup may already be pending, one is enough
+            let _ = self.wakeup.try_send(());
+        }
+    }
+
+    /// Write the buffer to the file
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// A logger that writes to a file and rotates it once it grows past `max_size_bytes`, and
+/// optionally at the start of every day (UTC).
+///
+/// Rotated files are renamed to `<log_file>.1`, `<log_file>.2`, ..., the lowest index being the
+/// most recent, and only the newest `max_files` of them are kept.
+pub struct RotatingFileWriter {
+    log_file_path: std::path::PathBuf,
+    max_size_bytes: u64,
+    max_files: usize,
+    daily_rotation: bool,
+    log_file: RwLock<RotatingFile>,
+}
+
+struct RotatingFile {
+    file: std::fs::File,
+    size: u64,
+    opened_on: chrono::NaiveDate,
+}
+
+impl RotatingFileWriter {
+    pub fn new(log_file: std::path::PathBuf, max_size_bytes: u64, max_files: usize) -> Self {
+        let file = Self::open(&log_file).expect("Unable to open log file");
+        Self {
+            log_file_path: log_file,
+            max_size_bytes,
+            max_files,
+            daily_rotation: false,
+            log_file: RwLock::new(file),
+        }
+    }
+
+    /// Also rotate the file on the first write of every day (UTC)
+    pub fn daily_rotation(mut self, daily_rotation: bool) -> Self {
+        self.daily_rotation = daily_rotation;
+        self
+    }
+
+    fn open(path: &std::path::Path) -> std::io::Result<RotatingFile> {
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let size = file.metadata()?.len();
+        Ok(RotatingFile {
+            file,
+            size,
+            opened_on: Utc::today().naive_utc(),
+        })
+    }
+
+    fn rotated_path(&self, index: usize) -> std::path::PathBuf {
+        let mut path = self.log_file_path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    /// Shifts the rotated files by one, dropping the oldest, and moves the current file to
+    /// `<log_file>.1`. Must be called with the write lock held so no line is written in between.
+    fn rotate(&self, current: &mut RotatingFile) -> std::io::Result<()> {
+        current.file.flush()?;
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.log_file_path)?;
+        } else {
+            let oldest = self.rotated_path(self.max_files);
+            if oldest.exists() {
+                std::fs::remove_file(oldest)?;
+            }
+            for index in (1..self.max_files).rev() {
+                let path = self.rotated_path(index);
+                if path.exists() {
+                    std::fs::rename(path, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.log_file_path, self.rotated_path(1))?;
+        }
+        *current = Self::open(&self.log_file_path)?;
+        Ok(())
+    }
+}
+
+impl Writer for RotatingFileWriter {
+    /// Write to file, rotating it first if needed
+    fn write(&self, log: String) {
+        let mut current = self.log_file.write();
+        let line_length = log.len() as u64 + 1;
+        let size_exceeded = current.size > 0 && current.size + line_length > self.max_size_bytes;
+        let new_day = self.daily_rotation && current.opened_on != Utc::today().naive_utc();
+        if size_exceeded || new_day {
+            // On failure keep appending to the current file rather than losing the line
+            if let Err(err) = self.rotate(&mut current) {
+                eprintln!("Unable to rotate log file: {}", err);
+            }
+        }
+
+        match writeln!(current.file, "{}", log) {
+            Ok(()) => current.size += line_length,
+            Err(err) => eprintln!("Unable to write to log file: {}", err),
+        }
+    }
+}
+
+/// Formats `entry` for the local printer. A failure is reported instead of propagated, as the
+/// formatter can be user supplied, and the entry isn't printed.
+fn format_local(
+    formatter: fn(&LogEntry) -> Result<String, fmt::Error>,
+    entry: &LogEntry,
+) -> Option<String> {
+    match formatter(entry) {
+        Ok(s) => Some(s),
+        Err(e) => {
+            STRUCT_LOG_FORMAT_ERROR_COUNT.inc();
+            eprintln!(
+                "[Logging] Unable to format log entry from {}: {}",
+                entry.metadata.location(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Converts a record into the JSON sent to logstash, e.g. to print JSON lines locally with
+/// `AptosDataBuilder::custom_format(json_format)`
+pub fn json_format(entry: &LogEntry) -> Result<String, fmt::Error> {
+    /// The entry with the message replaced
+    #[derive(Serialize)]
+    struct WithMessage<'a> {
+        #[serde(flatten)]
+        entry: &'a LogEntry,
+        message: String,
+    }
+
+    let json = if entry.message.is_some() {
+        serde_json::to_string(entry)
+    } else {
+        // XXX Temporary hack to ensure that log lines don't show up empty in kibana when the
+        // "message" field isn't set.
+        serde_json::to_string(&WithMessage {
+            entry,
+            message: serde_json::to_string(&entry.data).map_err(|_| fmt::Error)?,
+        })
+    };
+    json.map_err(|_| fmt::Error)
+}
+
 /// Converts a record into a string representation:
 /// UNIX_TIMESTAMP LOG_LEVEL [thread_name] FILE:LINE MESSAGE JSON_DATA
 /// Example:
@@ -630,7 +1516,9 @@ mod tests {
             let entry = LogEntry::new(
                 event,
                 ::std::thread::current().name(),
-                self.enable_backtrace,
+                self.enable_backtrace.then(|| Level::Error),
+                true,
+                TimestampFormat::default(),
             );
             self.sender.send(entry).unwrap();
         }
@@ -645,6 +1533,223 @@ mod tests {
         receiver
     }
 
+    #[test]
+    fn json_format_sets_missing_message() {
+        use super::json_format;
+
+        let mut data = std::collections::BTreeMap::new();
+        data.insert(Key::new("foo"), JsonValue::from(5));
+        let entry = LogEntry {
+            metadata: Metadata::new(Level::Info, "target", "module", "file.rs", 1, "file.rs:1"),
+            thread_name: None,
+            backtrace: None,
+            hostname: None,
+            timestamp: "2020-03-07T05:03:03.000000Z".to_string(),
+            data,
+            message: None,
+        };
+
+        let json: JsonValue = serde_json::from_str(&json_format(&entry).unwrap()).unwrap();
+        assert_eq!(json["message"], JsonValue::from(r#"{"foo":5}"#));
+        assert_eq!(json["data"]["foo"], JsonValue::from(5));
+        assert_eq!(json["level"], JsonValue::from("INFO"));
+    }
+
+    #[test]
+    fn redact() {
+        let mut data = std::collections::BTreeMap::new();
+        data.insert(Key::new("token"), JsonValue::from("secret"));
+        data.insert(Key::new("foo"), JsonValue::from(5));
+        let mut entry = LogEntry {
+            metadata: Metadata::new(Level::Info, "target", "module", "file.rs", 1, "file.rs:1"),
+            thread_name: None,
+            backtrace: None,
+            hostname: None,
+            timestamp: "2020-03-07T05:03:03.000000Z".to_string(),
+            data,
+            message: None,
+        };
+
+        let keys = vec![Key::new("token"), Key::new("missing")]
+            .into_iter()
+            .collect();
+        entry.redact(&keys);
+        assert_eq!(
+            entry.data.get(&Key::new("token")),
+            Some(&JsonValue::from("[REDACTED]"))
+        );
+        assert_eq!(entry.data.get(&Key::new("foo")), Some(&JsonValue::from(5)));
+        assert!(!entry.data.contains_key(&Key::new("missing")));
+    }
+
+    #[test]
+    fn format_error_skips_entry() {
+        use super::{AptosDataBuilder, RingBufferWriter};
+        use crate::counters::STRUCT_LOG_FORMAT_ERROR_COUNT;
+
+        let printer = RingBufferWriter::new(10);
+        let logger = AptosDataBuilder::new()
+            .printer(Box::new(printer.clone()))
+            .custom_format(|_| Err(std::fmt::Error))
+            .build_logger();
+        let entry = LogEntry {
+            metadata: Metadata::new(Level::Info, "target", "module", "file.rs", 1, "file.rs:1"),
+            thread_name: None,
+            backtrace: None,
+            hostname: None,
+            timestamp: "2020-03-07T05:03:03.000000Z".to_string(),
+            data: std::collections::BTreeMap::new(),
+            message: Some("message".to_string()),
+        };
+
+        let errors = STRUCT_LOG_FORMAT_ERROR_COUNT.get();
+        logger.send_entry(entry);
+        assert!(printer.snapshot().is_empty());
+        assert_eq!(STRUCT_LOG_FORMAT_ERROR_COUNT.get(), errors + 1);
+    }
+
+    #[test]
+    fn ring_buffer_writer() {
+        use super::{RingBufferWriter, Writer};
+
+        let writer = RingBufferWriter::new(2);
+        let reader = writer.clone();
+        for line in &["line1", "line2", "line3"] {
+            writer.write(line.to_string());
+        }
+
+        assert_eq!(reader.snapshot(), vec!["line2", "line3"]);
+        assert_eq!(reader.drain(), vec!["line2", "line3"]);
+        assert!(reader.snapshot().is_empty());
+    }
+
+    #[test]
+    fn timestamp_format() {
+        use super::TimestampFormat;
+
+        let time = DateTime::parse_from_rfc3339("2020-03-07T05:03:03.123456789Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            TimestampFormat::Rfc3339Micros.format(time),
+            "2020-03-07T05:03:03.123456Z"
+        );
+        assert_eq!(
+            TimestampFormat::Rfc3339Nanos.format(time),
+            "2020-03-07T05:03:03.123456789Z"
+        );
+        assert_eq!(TimestampFormat::UnixMillis.format(time), "1583557383123");
+        assert_eq!(
+            TimestampFormat::UnixNanos.format(time),
+            "1583557383123456789"
+        );
+    }
+
+    #[test]
+    fn gzip_remote_compression() {
+        use super::RemoteCompression;
+        use std::io::Read;
+
+        let batch = b"{\"level\":\"INFO\"}\n{\"level\":\"WARN\"}\n";
+        assert_eq!(&*RemoteCompression::None.encode(batch).unwrap(), &batch[..]);
+
+        let frame = RemoteCompression::Gzip.encode(batch).unwrap();
+        let length = u32::from_be_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+        assert_eq!(length, frame.len() - 4);
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(&frame[4..])
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, batch);
+    }
+
+    #[test]
+    fn key_sampler() {
+        use super::KeySampler;
+        use std::time::{Duration, Instant};
+
+        let sampler = KeySampler::new((Key::new("account"), Duration::from_secs(1)));
+        let data = |account: &str| -> std::collections::BTreeMap<Key, JsonValue> {
+            vec![(Key::new("account"), JsonValue::from(account))]
+                .into_iter()
+                .collect()
+        };
+        let start = Instant::now();
+
+        assert!(sampler.allow(&data("0x1"), start));
+        assert!(!sampler.allow(&data("0x1"), start + Duration::from_millis(500)));
+        // Values are sampled independently
+        assert!(sampler.allow(&data("0x2"), start + Duration::from_millis(500)));
+        assert!(sampler.allow(&data("0x1"), start + Duration::from_secs(1)));
+        // Entries without the field aren't sampled
+        let no_account = std::collections::BTreeMap::new();
+        assert!(sampler.allow(&no_account, start));
+        assert!(sampler.allow(&no_account, start));
+    }
+
+    #[test]
+    fn call_site_rate_limiter() {
+        use super::CallSiteRateLimiter;
+
+        let first = Metadata::new(Level::Error, "target", "module", "file.rs", 1, "file.rs:1");
+        let second = Metadata::new(Level::Error, "target", "module", "file.rs", 2, "file.rs:2");
+        let limiter = CallSiteRateLimiter::new(2);
+
+        assert!(limiter.allow_at(&first, 10));
+        assert!(limiter.allow_at(&first, 10));
+        assert!(!limiter.allow_at(&first, 10));
+        // Call sites are limited independently
+        assert!(limiter.allow_at(&second, 10));
+        // The budget is reset every second
+        assert!(limiter.allow_at(&first, 11));
+    }
+
+    #[test]
+    fn buffered_file_writer() {
+        use super::{BufferedFileWriter, Writer};
+        use std::time::Duration;
+
+        let log_file =
+            std::env::temp_dir().join(format!("aptos-logger-buffered-{}.log", std::process::id()));
+        let writer = BufferedFileWriter::new(log_file.clone(), 1024, Duration::from_secs(3600));
+        writer.write("line1".to_string());
+        writer.write("line2".to_string());
+
+        // Nothing is written until the buffer is flushed
+        assert_eq!(std::fs::read_to_string(&log_file).unwrap(), "");
+        writer.flush();
+        assert_eq!(
+            std::fs::read_to_string(&log_file).unwrap(),
+            "line1\nline2\n"
+        );
+
+        std::fs::remove_file(log_file).unwrap();
+    }
+
+    #[test]
+    fn rotating_file_writer() {
+        use super::{RotatingFileWriter, Writer};
+
+        let dir =
+            std::env::temp_dir().join(format!("aptos-logger-rotation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_file = dir.join("node.log");
+        let writer = RotatingFileWriter::new(log_file.clone(), 10, 2);
+
+        // Each line is 6 bytes with the newline, so every line after the first rotates the file
+        for line in &["line1", "line2", "line3", "line4"] {
+            writer.write(line.to_string());
+        }
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("node.log"), "line4\n");
+        assert_eq!(read("node.log.1"), "line3\n");
+        assert_eq!(read("node.log.2"), "line2\n");
+        assert!(!dir.join("node.log.3").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     // TODO: Find a better mechanism for testing that allows setting the logger not globally
     #[test]
     fn basic() {
@@ -714,6 +1819,30 @@ mod tests {
             Some("name"),
         );
 
+        // Global fields are added, but don't override the ones set at the call site
+        let mut entry = entry;
+        let global_fields = vec![
+            (Key::new("category"), JsonValue::from("global")),
+            (Key::new("chain_id"), JsonValue::from(4)),
+        ]
+        .into_iter()
+        .collect();
+        entry.add_global_fields(&global_fields);
+        assert_eq!(
+            entry
+                .data
+                .get(&Key::new("category"))
+                .and_then(JsonValue::as_str),
+            Some("name"),
+        );
+        assert_eq!(
+            entry
+                .data
+                .get(&Key::new("chain_id"))
+                .and_then(JsonValue::as_u64),
+            Some(4),
+        );
+
         // Test error logs contain backtraces
         error!("This is an error log");
         let entry = receiver.recv().unwrap();
@@ -3,7 +3,10 @@
 
 //! Logging metrics for determining quality of log submission
 use once_cell::sync::Lazy;
-use prometheus::{register_int_counter, IntCounter};
+use prometheus::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
 
 /// Count of the struct logs submitted by macro
 pub static STRUCT_LOG_COUNT: Lazy<IntCounter> = Lazy::new(|| {
@@ -37,6 +40,16 @@ pub static SENT_STRUCT_LOG_BYTES: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of bytes written to TCP for struct logs, which is less than
+/// `aptos_struct_log_tcp_submit_bytes` if compression is enabled
+pub static SENT_STRUCT_LOG_WIRE_BYTES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_tcp_submit_wire_bytes",
+        "Number of bytes of the struct logs written to TCP, after compression."
+    )
+    .unwrap()
+});
+
 /// Metric for when we connect the outbound TCP
 pub static STRUCT_LOG_TCP_CONNECT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -46,6 +59,42 @@ pub static STRUCT_LOG_TCP_CONNECT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Metric for attempts to reconnect to a remote endpoint
+pub static STRUCT_LOG_RECONNECT_ATTEMPT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_reconnect_attempt_count",
+        "Count of the attempts to (re)connect to a remote endpoint for struct logs."
+    )
+    .unwrap()
+});
+
+/// Metric for successful reconnections to a remote endpoint
+pub static STRUCT_LOG_RECONNECT_SUCCESS_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_reconnect_success_count",
+        "Count of the successful (re)connections to a remote endpoint for struct logs."
+    )
+    .unwrap()
+});
+
+/// Approximate number of events waiting in the queue to be processed by the logger service
+pub static STRUCT_LOG_QUEUE_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_struct_log_queue_depth",
+        "Number of struct log events waiting to be processed."
+    )
+    .unwrap()
+});
+
+/// Capacity of the queue of the logger service, alert on `aptos_struct_log_queue_depth` nearing it
+pub static STRUCT_LOG_QUEUE_CAPACITY: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_struct_log_queue_capacity",
+        "Configured number of struct log events the queue can hold."
+    )
+    .unwrap()
+});
+
 /// Metric for when we fail to log during sending to the queue
 pub static STRUCT_LOG_QUEUE_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -64,6 +113,53 @@ pub static STRUCT_LOG_SEND_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Metric for retries while sending struct logs, per remote endpoint
+pub static STRUCT_LOG_ENDPOINT_SEND_RETRY_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_struct_log_endpoint_send_retry_count",
+        "Count of the retries while sending struct logs, per remote endpoint.",
+        &["endpoint"]
+    )
+    .unwrap()
+});
+
+/// Metric for when we fail to log during sending, per remote endpoint
+pub static STRUCT_LOG_ENDPOINT_SEND_ERROR_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_struct_log_endpoint_send_error_count",
+        "Count of all errors during sending struct logs, per remote endpoint.",
+        &["endpoint"]
+    )
+    .unwrap()
+});
+
+/// Metric for logs dropped by the per call site rate limit, as opposed to a full queue
+pub static STRUCT_LOG_RATE_LIMITED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_rate_limited_count",
+        "Count of the struct logs dropped by the per call site rate limit."
+    )
+    .unwrap()
+});
+
+/// Metric for logs dropped by sampling on the value of a structured field
+pub static STRUCT_LOG_KEY_SAMPLED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_key_sampled_count",
+        "Count of the struct logs dropped by sampling on the value of a field."
+    )
+    .unwrap()
+});
+
+/// Metric for entries the local formatter failed to format, which aren't printed
+pub static STRUCT_LOG_FORMAT_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_format_error_count",
+        "Count of all errors while formatting struct logs for the local printer."
+    )
+    .unwrap()
+});
+
 pub static STRUCT_LOG_CONNECT_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
         "aptos_struct_log_connect_error_count",
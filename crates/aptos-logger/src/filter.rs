@@ -118,6 +118,7 @@ impl Builder {
 
         Filter {
             directives: ::std::mem::take(&mut self.directives),
+            overrides: Vec::new(),
         }
     }
 }
@@ -126,6 +127,8 @@ impl Builder {
 #[derive(Debug)]
 pub struct Filter {
     directives: Vec<Directive>,
+    /// Per module directives set at runtime, taking precedence over `directives`
+    overrides: Vec<Directive>,
 }
 
 impl Filter {
@@ -134,14 +137,42 @@ impl Filter {
     }
 
     pub fn enabled(&self, metadata: &Metadata) -> bool {
+        Self::find_enabled(&self.overrides, metadata)
+            .or_else(|| Self::find_enabled(&self.directives, metadata))
+            .unwrap_or(false)
+    }
+
+    /// Whether `metadata` is enabled by the longest matching directive, `None` if none matches
+    fn find_enabled(directives: &[Directive], metadata: &Metadata) -> Option<bool> {
         // Search for the longest match, the vector is assumed to be pre-sorted.
-        for directive in self.directives.iter().rev() {
+        for directive in directives.iter().rev() {
             match &directive.name {
                 Some(name) if !metadata.module_path().starts_with(name) => {}
-                Some(..) | None => return LevelFilter::from(metadata.level()) <= directive.level,
+                Some(..) | None => {
+                    return Some(LevelFilter::from(metadata.level()) <= directive.level)
+                }
             }
         }
-        false
+        None
+    }
+
+    /// Log `module` at most at `level`, regardless of the directives the filter was built with,
+    /// until the override is cleared.
+    pub fn set_override(&mut self, module: &str, level: LevelFilter) {
+        self.overrides
+            .retain(|directive| directive.name.as_deref() != Some(module));
+        self.overrides.push(Directive::new(Some(module), level));
+        // Keep the overrides sorted by length of their name, like the directives
+        self.overrides
+            .sort_by_key(|directive| directive.name.as_ref().map(|name| name.len()));
+    }
+
+    /// Remove the override of `module`, returning whether there was one
+    pub fn clear_override(&mut self, module: &str) -> bool {
+        let num_overrides = self.overrides.len();
+        self.overrides
+            .retain(|directive| directive.name.as_deref() != Some(module));
+        self.overrides.len() != num_overrides
     }
 }
 
@@ -277,6 +308,26 @@ mod tests {
         assert!(logger.enabled(&make_metadata(Level::Info, "crate2::mod2")));
     }
 
+    #[test]
+    fn override_module_level() {
+        let mut logger = Builder::new()
+            .filter(None, LevelFilter::Info)
+            .filter(Some("crate1::mod1"), LevelFilter::Warn)
+            .build();
+
+        logger.set_override("crate1", LevelFilter::Debug);
+        assert!(logger.enabled(&make_metadata(Level::Debug, "crate1::mod1")));
+        assert!(!logger.enabled(&make_metadata(Level::Debug, "crate2")));
+
+        logger.set_override("crate1", LevelFilter::Error);
+        assert!(!logger.enabled(&make_metadata(Level::Warn, "crate1::mod1")));
+
+        assert!(logger.clear_override("crate1"));
+        assert!(!logger.clear_override("crate1"));
+        assert!(logger.enabled(&make_metadata(Level::Warn, "crate1::mod1")));
+        assert!(!logger.enabled(&make_metadata(Level::Info, "crate1::mod1")));
+    }
+
     #[test]
     fn parse_valid() {
         let mut builder = Builder::new();
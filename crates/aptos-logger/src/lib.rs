@@ -153,8 +153,11 @@ mod metadata;
 pub mod sample;
 pub mod tracing_adapter;
 
+mod queue;
 mod security;
 mod struct_log;
+#[cfg(unix)]
+mod syslog;
 
 pub use crate::aptos_logger::{AptosData as Logger, AptosDataBuilder, Writer, CHANNEL_SIZE};
 pub use event::Event;
@@ -165,5 +168,8 @@ pub use metadata::{Level, Metadata};
 pub use aptos_log_derive::Schema;
 pub use kv::{Key, KeyValue, Schema, Value, Visitor};
 pub use security::SecurityEvent;
+pub use struct_log::TlsOptions;
+#[cfg(unix)]
+pub use syslog::SyslogWriter;
 
 mod counters;
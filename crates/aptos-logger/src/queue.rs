@@ -0,0 +1,208 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded queue whose capacity can be changed while it's in use, which `std::sync::mpsc`
+//! channels don't allow
+
+use aptos_infallible::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::{mpsc::RecvTimeoutError, Condvar},
+    time::{Duration, Instant},
+};
+
+pub(crate) struct BoundedQueue<T> {
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    /// The largest capacity the queue can be given
+    max_capacity: usize,
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    /// Set once the receiving side is gone, after which nothing can be sent
+    closed: bool,
+}
+
+impl<T> BoundedQueue<T> {
+    /// A queue holding up to `capacity` items, which can be grown up to `max_capacity` later
+    pub fn new(capacity: usize, max_capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            state: Mutex::new(QueueState {
+                items: VecDeque::with_capacity(capacity),
+                capacity,
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            max_capacity: max_capacity.max(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.state.lock().capacity
+    }
+
+    /// Changes the capacity, clamped to `[1, max_capacity]`, and returns the new capacity. Items
+    /// already queued beyond a reduced capacity are kept.
+    pub fn set_capacity(&self, capacity: usize) -> usize {
+        let capacity = capacity.clamp(1, self.max_capacity);
+        self.state.lock().capacity = capacity;
+        self.not_full.notify_all();
+        capacity
+    }
+
+    /// Queues `item` if there is room, otherwise gives it back
+    pub fn try_send(&self, item: T) -> Result<(), T> {
+        let mut state = self.state.lock();
+        if state.closed || state.items.len() >= state.capacity {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Queues `item`, waiting for room if needed. Gives it back if the queue is closed.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        let mut state = self.state.lock();
+        while !state.closed && state.items.len() >= state.capacity {
+            state = self
+                .not_full
+                .wait(state)
+                .expect("Cannot currently handle a poisoned lock");
+        }
+        if state.closed {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Queues `item`, waiting up to `timeout` for room. Gives it back if the queue is closed or
+    /// is still full by then.
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), T> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock();
+        while !state.closed && state.items.len() >= state.capacity {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(item);
+            }
+            state = self
+                .not_full
+                .wait_timeout(state, deadline - now)
+                .expect("Cannot currently handle a poisoned lock")
+                .0;
+        }
+        if state.closed {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Waits for the next item, `None` if the queue is closed and empty
+    pub fn recv(&self) -> Option<T> {
+        let mut state = self.state.lock();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                self.not_full.notify_one();
+                return Some(item);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self
+                .not_empty
+                .wait(state)
+                .expect("Cannot currently handle a poisoned lock");
+        }
+    }
+
+    /// Waits up to `timeout` for the next item
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                self.not_full.notify_one();
+                return Ok(item);
+            }
+            if state.closed {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self
+                .not_empty
+                .wait_timeout(state, deadline - now)
+                .expect("Cannot currently handle a poisoned lock")
+                .0;
+        }
+    }
+
+    /// Rejects any further item, e.g. once the receiver stops
+    pub fn close(&self) {
+        self.state.lock().closed = true;
+        self.not_full.notify_all();
+        self.not_empty.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundedQueue;
+    use std::{sync::mpsc::RecvTimeoutError, time::Duration};
+
+    #[test]
+    fn resize() {
+        let queue = BoundedQueue::new(1, 3);
+        assert!(queue.try_send(1).is_ok());
+        assert_eq!(queue.try_send(2), Err(2));
+
+        // Growing is capped at the max capacity
+        assert_eq!(queue.set_capacity(10), 3);
+        assert!(queue.try_send(2).is_ok());
+        assert!(queue.try_send(3).is_ok());
+        assert_eq!(queue.try_send(4), Err(4));
+
+        // Shrinking keeps the queued items
+        assert_eq!(queue.set_capacity(1), 1);
+        assert_eq!(queue.recv(), Some(1));
+        assert_eq!(queue.try_send(4), Err(4));
+        assert_eq!(queue.recv(), Some(2));
+        assert_eq!(queue.recv(), Some(3));
+        assert_eq!(
+            queue.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Timeout)
+        );
+    }
+
+    #[test]
+    fn send_timeout() {
+        let queue = BoundedQueue::new(1, 1);
+        assert!(queue.send_timeout(1, Duration::from_millis(1)).is_ok());
+        assert_eq!(queue.send_timeout(2, Duration::from_millis(1)), Err(2));
+        assert_eq!(queue.recv(), Some(1));
+        assert!(queue.send_timeout(2, Duration::from_millis(1)).is_ok());
+    }
+
+    #[test]
+    fn close() {
+        let queue = BoundedQueue::new(2, 2);
+        queue.send(1).unwrap();
+        queue.close();
+
+        assert_eq!(queue.send(2), Err(2));
+        assert_eq!(queue.recv(), Some(1));
+        assert_eq!(queue.recv(), None);
+    }
+}
@@ -6,16 +6,91 @@
 //! Handles sending logs under disconnects, and retries.  Tries to continue to make progress on a
 //! log but eventually drops older logs to continue to make progress on newer logs.
 
-use crate::counters::{STRUCT_LOG_CONNECT_ERROR_COUNT, STRUCT_LOG_TCP_CONNECT_COUNT};
+use crate::counters::{
+    STRUCT_LOG_CONNECT_ERROR_COUNT, STRUCT_LOG_RECONNECT_ATTEMPT_COUNT,
+    STRUCT_LOG_RECONNECT_SUCCESS_COUNT, STRUCT_LOG_TCP_CONNECT_COUNT,
+};
+use rustls::{ClientConfig, ClientSession, StreamOwned};
 use std::{
+    fs::File,
     io,
-    io::Write,
+    io::{BufReader, Write},
     net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
 const WRITE_TIMEOUT_MS: u64 = 2000;
 const CONNECTION_TIMEOUT_MS: u64 = 5000;
+/// Default delay before the first retry of a failed send
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// Default upper bound of the delay between retries of a failed send
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_millis(2000);
+
+/// How to secure the connection to a remote log endpoint with TLS
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// PEM file with the certificates to trust, the webpki roots are used if `None`
+    pub ca_cert_path: Option<PathBuf>,
+    /// The name to verify the server certificate against, the host of the endpoint if `None`
+    pub server_name: Option<String>,
+}
+
+impl TlsOptions {
+    fn client_config(&self) -> io::Result<Arc<ClientConfig>> {
+        let mut config = ClientConfig::new();
+        if let Some(path) = &self.ca_cert_path {
+            let mut reader = BufReader::new(File::open(path)?);
+            let (valid, _) = config.root_store.add_pem_file(&mut reader).map_err(|()| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unable to parse CA certificates in {:?}", path),
+                )
+            })?;
+            if valid == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("No valid CA certificate in {:?}", path),
+                ));
+            }
+        } else {
+            config
+                .root_store
+                .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+        }
+        Ok(Arc::new(config))
+    }
+}
+
+/// TLS settings of a `TcpWriter`, resolved once when the writer is created
+struct TlsSettings {
+    /// The client config, or why it couldn't be built, which is reported on every connection
+    config: Result<Arc<ClientConfig>, String>,
+    server_name: String,
+}
+
+/// The connection to the endpoint, either plaintext or over TLS
+enum LogStream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ClientSession, TcpStream>>),
+}
+
+impl Write for LogStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            LogStream::Plain(stream) => stream.write(buf),
+            LogStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            LogStream::Plain(stream) => stream.flush(),
+            LogStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
 
 /// A wrapper for `TcpStream` that handles reconnecting to the endpoint automatically
 ///
@@ -23,9 +98,15 @@ const CONNECTION_TIMEOUT_MS: u64 = 5000;
 pub(crate) struct TcpWriter {
     /// The DNS name or IP address logs are being sent to
     endpoint: String,
-    /// The `TCPStream` to write to, which will be `None` when disconnected
-    stream: Option<TcpStream>,
+    /// The stream to write to, which will be `None` when disconnected
+    stream: Option<LogStream>,
     last_connection_attempt: Option<Instant>,
+    /// Set if the connection must be secured with TLS
+    tls: Option<TlsSettings>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// Delay before the next retry, doubled on every retry and reset on reconnection
+    next_backoff: Duration,
 }
 
 impl TcpWriter {
@@ -34,6 +115,33 @@ impl TcpWriter {
             endpoint,
             stream: None,
             last_connection_attempt: None,
+            tls: None,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            next_backoff: DEFAULT_INITIAL_BACKOFF,
+        }
+    }
+
+    /// A writer that sends logs to `endpoint` over TLS. A TLS session is established on every
+    /// (re)connection.
+    pub fn with_tls(endpoint: String, options: &TlsOptions) -> Self {
+        let config = options.client_config().map_err(|e| {
+            eprintln!("[Logging] Invalid TLS configuration: {}", e);
+            e.to_string()
+        });
+        let server_name = options.server_name.clone().unwrap_or_else(|| {
+            // Strip the port from `host:port`
+            match endpoint.rfind(':') {
+                Some(index) => endpoint[..index].to_string(),
+                None => endpoint.clone(),
+            }
+        });
+        Self {
+            tls: Some(TlsSettings {
+                config,
+                server_name,
+            }),
+            ..Self::new(endpoint)
         }
     }
 
@@ -41,6 +149,21 @@ impl TcpWriter {
         &self.endpoint
     }
 
+    /// Wait `initial` before the first retry of a failed send, doubling the delay on every
+    /// retry up to `max`
+    pub fn set_backoff(&mut self, initial: Duration, max: Duration) {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self.next_backoff = initial;
+    }
+
+    /// The delay to wait before the next retry, increasing the one after it
+    pub fn backoff(&mut self) -> Duration {
+        let backoff = self.next_backoff;
+        self.next_backoff = (backoff * 2).min(self.max_backoff);
+        backoff
+    }
+
     /// Ensure that we get a connection, no matter how long it takes
     /// This will block until there is a connection
     fn refresh_connection(&mut self) -> io::Result<()> {
@@ -51,9 +174,12 @@ impl TcpWriter {
             .unwrap_or(true)
         {
             self.last_connection_attempt = Some(Instant::now());
+            STRUCT_LOG_RECONNECT_ATTEMPT_COUNT.inc();
             match self.connect() {
                 Ok(stream) => {
+                    STRUCT_LOG_RECONNECT_SUCCESS_COUNT.inc();
                     self.stream = Some(stream);
+                    self.next_backoff = self.initial_backoff;
                     Ok(())
                 }
                 Err(e) => {
@@ -70,8 +196,32 @@ impl TcpWriter {
         }
     }
 
+    /// Connect, and set up a TLS session on top of the connection if required
+    fn connect(&mut self) -> io::Result<LogStream> {
+        let stream = self.connect_tcp()?;
+        let tls = match &self.tls {
+            Some(tls) => tls,
+            None => return Ok(LogStream::Plain(stream)),
+        };
+
+        let config = tls
+            .config
+            .as_ref()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.clone()))?;
+        let server_name =
+            webpki::DNSNameRef::try_from_ascii_str(&tls.server_name).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid TLS server name {}: {}", tls.server_name, e),
+                )
+            })?;
+        // The handshake happens on the first write, certificate errors surface from there
+        let session = ClientSession::new(config, server_name);
+        Ok(LogStream::Tls(Box::new(StreamOwned::new(session, stream))))
+    }
+
     /// Connect and ensure the write timeout is set
-    fn connect(&mut self) -> io::Result<TcpStream> {
+    fn connect_tcp(&mut self) -> io::Result<TcpStream> {
         STRUCT_LOG_TCP_CONNECT_COUNT.inc();
 
         let mut last_error = io::Error::new(
@@ -122,7 +272,7 @@ impl Write for TcpWriter {
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        if let Some(mut stream) = self.stream.as_ref() {
+        if let Some(stream) = self.stream.as_mut() {
             stream.flush()
         } else {
             Err(io::Error::new(
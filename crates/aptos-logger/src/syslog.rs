@@ -0,0 +1,123 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `Writer` sending logs to the local syslog daemon, in the RFC 5424 format
+
+use crate::{aptos_logger::Writer, Level};
+use chrono::{SecondsFormat, Utc};
+use std::{
+    os::unix::net::UnixDatagram,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The sockets the syslog daemon usually listens on
+const SYSLOG_SOCKETS: &[&str] = &["/dev/log", "/var/run/syslog", "/var/run/log"];
+/// The `daemon` facility
+const FACILITY: u8 = 3;
+
+/// A logger that writes to the local syslog socket, falling back to stderr if it's unavailable
+pub struct SyslogWriter {
+    app_name: String,
+    hostname: String,
+    socket: Option<UnixDatagram>,
+    /// Whether the fallback to stderr has been reported already
+    warned: AtomicBool,
+}
+
+impl SyslogWriter {
+    /// A writer reporting logs as coming from `app_name`
+    pub fn new(app_name: String) -> Self {
+        let socket = UnixDatagram::unbound().ok().and_then(|socket| {
+            SYSLOG_SOCKETS
+                .iter()
+                .any(|path| socket.connect(path).is_ok())
+                .then(|| socket)
+        });
+        let hostname = hostname::get()
+            .ok()
+            .and_then(|name| name.into_string().ok())
+            .unwrap_or_else(|| "-".to_string());
+        Self {
+            app_name,
+            hostname,
+            socket,
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    fn severity(level: Level) -> u8 {
+        match level {
+            Level::Error => 3,
+            Level::Warn => 4,
+            Level::Info => 6,
+            Level::Debug | Level::Trace => 7,
+        }
+    }
+
+    /// Formats `log` as an RFC 5424 message
+    fn format(&self, level: Level, log: &str) -> String {
+        format!(
+            "<{}>1 {} {} {} {} - - {}",
+            FACILITY * 8 + Self::severity(level),
+            Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            log
+        )
+    }
+
+    fn fallback(&self, log: String, reason: &str) {
+        if !self.warned.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "[Logging] Syslog unavailable ({}), logging to stderr",
+                reason
+            );
+        }
+        eprintln!("{}", log);
+    }
+}
+
+impl Writer for SyslogWriter {
+    /// Write log to syslog at the `Info` severity
+    fn write(&self, log: String) {
+        self.write_with_level(Level::Info, log)
+    }
+
+    /// Write log to syslog at the severity of `level`
+    fn write_with_level(&self, level: Level, log: String) {
+        match &self.socket {
+            Some(socket) => {
+                if let Err(e) = socket.send(self.format(level, &log).as_bytes()) {
+                    self.fallback(log, &e.to_string());
+                }
+            }
+            None => self.fallback(log, "no syslog socket"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyslogWriter;
+    use crate::Level;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn format() {
+        let writer = SyslogWriter {
+            app_name: "aptos-node".to_string(),
+            hostname: "host".to_string(),
+            socket: None,
+            warned: AtomicBool::new(false),
+        };
+        let message = writer.format(Level::Warn, "hello");
+
+        // daemon facility (3) * 8 + warning severity (4)
+        assert!(message.starts_with("<28>1 "));
+        assert!(message.ends_with(&format!(
+            " host aptos-node {} - - hello",
+            std::process::id()
+        )));
+    }
+}
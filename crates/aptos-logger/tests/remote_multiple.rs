@@ -0,0 +1,52 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::{aptos_logger::AptosData, error, Level};
+use serde::Deserialize;
+use std::{
+    io::{BufRead, BufReader},
+    net::TcpListener,
+};
+
+#[derive(Deserialize)]
+struct Log {
+    level: Level,
+}
+
+fn read_log(listener: &TcpListener) -> Log {
+    let (stream, _) = listener.accept().unwrap();
+    let mut stream = BufReader::new(stream);
+    let mut buf = Vec::new();
+    stream.read_until(b'\n', &mut buf).unwrap();
+    serde_json::from_slice(&buf).unwrap()
+}
+
+#[test]
+fn remote_multiple_destinations() {
+    std::env::set_var("RUST_LOG_REMOTE", "debug");
+
+    let first = TcpListener::bind("127.0.0.1:0").unwrap();
+    let second = TcpListener::bind("127.0.0.1:0").unwrap();
+    // Nothing listens on this address, writing to it must not stop the other destinations
+    let unreachable = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+
+    AptosData::builder()
+        .address(first.local_addr().unwrap().to_string())
+        .add_address(unreachable)
+        .add_address(second.local_addr().unwrap().to_string())
+        .is_async(true)
+        .build();
+
+    let handle = std::thread::spawn(|| {
+        error!("Hello");
+        aptos_logger::flush();
+    });
+
+    assert_eq!(read_log(&first).level, Level::Error);
+    assert_eq!(read_log(&second).level, Level::Error);
+
+    handle.join().unwrap();
+}
@@ -0,0 +1,31 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::{
+    aptos_logger::{AptosData, RingBufferWriter},
+    info, Level,
+};
+
+#[test]
+fn shutdown_drains_queued_entries() {
+    let writer = RingBufferWriter::new(100);
+    let logger = AptosData::builder()
+        .is_async(true)
+        .level(Level::Info)
+        .printer(Box::new(writer.clone()))
+        .build();
+
+    for i in 0..10 {
+        info!("entry {}", i);
+    }
+
+    assert!(logger.shutdown());
+    let lines = writer.snapshot();
+    assert_eq!(lines.len(), 10);
+    assert!(lines[9].ends_with("entry 9"));
+
+    // Logging and flushing after the shutdown doesn't block or panic
+    info!("dropped");
+    aptos_logger::flush();
+    assert_eq!(writer.snapshot().len(), 10);
+}
@@ -0,0 +1,45 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Client;
+use anyhow::Result;
+use aptos_types::{
+    access_path::AccessPath, on_chain_config::ConfigStorage, state_store::state_key::StateKey,
+};
+use tokio::runtime::Runtime;
+
+/// A `ConfigStorage` that reads the on-chain configs at `version` from a fullnode's REST API, so
+/// typed configs can be fetched with `OnChainConfig::fetch_config` without a local DB.
+///
+/// Requests are run to completion on an internal runtime, so this must not be used from within
+/// an async context.
+pub struct RestConfigStorage {
+    client: Client,
+    version: u64,
+    runtime: Runtime,
+}
+
+impl RestConfigStorage {
+    pub fn new(client: Client, version: u64) -> Result<Self> {
+        Ok(Self {
+            client,
+            version,
+            runtime: Runtime::new()?,
+        })
+    }
+}
+
+impl ConfigStorage for RestConfigStorage {
+    /// Returns `None` if the config doesn't exist at `version` or the request failed.
+    fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
+        self.runtime
+            .block_on(
+                self.client.get_raw_state_value_at_version(
+                    &StateKey::AccessPath(access_path),
+                    self.version,
+                ),
+            )
+            .ok()
+            .flatten()
+    }
+}
@@ -2,20 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
-use aptos_api_types::mime_types::BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE;
-pub use aptos_api_types::{self, MoveModuleBytecode, PendingTransaction, Transaction};
+use aptos_api_types::mime_types::{BCS, BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE};
+pub use aptos_api_types::{
+    self, MoveModuleBytecode, PendingTransaction, Transaction, TransactionOnChainData,
+};
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress, account_config::aptos_root_address,
+    contract_event::EventWithVersion, event::EventKey, state_store::state_key::StateKey,
     transaction::SignedTransaction,
 };
-use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Client as ReqwestClient, StatusCode,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use state::State;
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 use url::Url;
 
+mod config_storage;
+pub use config_storage::RestConfigStorage;
 pub mod error;
 pub mod faucet;
 pub use faucet::FaucetClient;
@@ -195,6 +203,27 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_transactions_bcs(
+        &self,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<TransactionOnChainData>>> {
+        let url = self.base_url.join("transactions")?;
+
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+
+        self.bcs(response).await
+    }
+
     pub async fn get_transaction(&self, hash: HashValue) -> Result<Response<Transaction>> {
         self.json(
             self.get_transaction_by_version_or_hash(hash.to_hex_literal())
@@ -274,6 +303,22 @@ impl Client {
         self.json(response).await
     }
 
+    /// Fetch the raw BCS bytes of every resource of `address` at `version`, keyed by type.
+    pub async fn get_account_resources_bcs_at_version(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<BTreeMap<StructTag, Vec<u8>>>> {
+        let url = self.base_url.join(&format!(
+            "accounts/{}/resources?version={}",
+            address, version
+        ))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+
+        self.bcs(response).await
+    }
+
     pub async fn get_resource<T: DeserializeOwned>(
         &self,
         address: AccountAddress,
@@ -334,6 +379,40 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_account_modules_at_version(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<Vec<MoveModuleBytecode>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/modules?version={}", address, version))?;
+
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
+    /// Fetch the raw BCS bytes stored under `state_key` at `version`, or `None` if the key doesn't
+    /// exist at that version.
+    pub async fn get_raw_state_value_at_version(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        let url = self.base_url.join(&format!(
+            "state/raw/0x{}?version={}",
+            hex::encode(bcs::to_bytes(state_key)?),
+            version
+        ))?;
+
+        let response = self.inner.get(url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let (response, _) = self.check_response(response).await?;
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
     pub async fn get_table_item<K: Serialize>(
         &self,
         table_handle: u128,
@@ -354,6 +433,34 @@ impl Client {
         self.json(response).await
     }
 
+    /// Fetch the events of the stream `key`, each with the version of the transaction that
+    /// emitted it.
+    pub async fn get_events_bcs(
+        &self,
+        key: &EventKey,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<EventWithVersion>>> {
+        let url = self.base_url.join(&format!("events/0x{}", key))?;
+
+        let mut request = self
+            .inner
+            .get(url)
+            .header(ACCEPT, BCS)
+            .query(&[("with_versions", true)]);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+
+        self.bcs(response).await
+    }
+
     pub async fn get_account(&self, address: AccountAddress) -> Result<Response<Account>> {
         let url = self.base_url.join(&format!("accounts/{}", address))?;
         let response = self.inner.get(url).send().await?;
@@ -382,6 +489,15 @@ impl Client {
         Ok(Response::new(json, state))
     }
 
+    async fn bcs<T: serde::de::DeserializeOwned>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<Response<T>> {
+        let (response, state) = self.check_response(response).await?;
+        let bytes = response.bytes().await?;
+        Ok(Response::new(bcs::from_bytes(&bytes)?, state))
+    }
+
     pub async fn health_check(&self, seconds: u64) -> Result<()> {
         let url = self.base_url.join("-/healthy")?;
         let response = self
@@ -4,8 +4,9 @@
 use crate::{
     evaluators::{
         direct::{
-            get_node_identity, LatencyEvaluatorArgs, NodeIdentityEvaluatorArgs, TpsEvaluatorArgs,
-            TransactionPresenceEvaluatorArgs,
+            get_node_identity, ApiBuildVersionEvaluatorArgs, EpochMatchEvaluatorArgs,
+            LatencyEvaluatorArgs, NodeIdentityEvaluatorArgs, TpsEvaluatorArgs,
+            TransactionPresenceEvaluatorArgs, VersionLagEvaluatorArgs,
         },
         metrics::{
             ConsensusProposalsEvaluatorArgs, ConsensusRoundEvaluatorArgs,
@@ -22,6 +23,7 @@ use aptos_sdk::types::chain_id::ChainId;
 use clap::Parser;
 use once_cell::sync::Lazy;
 use poem_openapi::{types::Example, Object as PoemObject};
+use reqwest::RequestBuilder;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -104,13 +106,16 @@ impl NodeConfiguration {
     /// If chain_id and role_type are already set, we validate that the values
     /// match up. If they're not set, we set them using the values we find.
     pub async fn fetch_additional_configuration(&mut self) -> Result<()> {
+        let node_identity_args = &self.evaluator_args.node_identity_args;
         let (reported_chain_id, reported_role_type) =
-            get_node_identity(&self.node_address).await.map_err(|e| {
-                format_err!(
-                    "Failed to fetch chain ID and role type for baseline node configuration: {}",
-                    e
-                )
-            })?;
+            get_node_identity(&self.node_address, node_identity_args)
+                .await
+                .map_err(|e| {
+                    format_err!(
+                "Failed to fetch chain ID and role type for baseline node configuration: {}",
+                e
+            )
+                })?;
         if let Some(configured_chain_id) = self.chain_id {
             if configured_chain_id != reported_chain_id {
                 bail!("Chain ID mismatch: The baseline configuration {} says the chain ID is {} but the node reports chain ID {}", self.configuration_name, configured_chain_id, reported_chain_id);
@@ -129,6 +134,9 @@ impl NodeConfiguration {
 
 #[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
 pub struct EvaluatorArgs {
+    #[clap(flatten)]
+    pub api_build_version_args: ApiBuildVersionEvaluatorArgs,
+
     #[clap(flatten)]
     pub build_version_args: BuildVersionEvaluatorArgs,
 
@@ -141,6 +149,9 @@ pub struct EvaluatorArgs {
     #[clap(flatten)]
     pub consensus_timeouts_args: ConsensusTimeoutsEvaluatorArgs,
 
+    #[clap(flatten)]
+    pub epoch_match_args: EpochMatchEvaluatorArgs,
+
     #[clap(flatten)]
     pub latency_args: LatencyEvaluatorArgs,
 
@@ -162,6 +173,9 @@ pub struct EvaluatorArgs {
 
     #[clap(flatten)]
     pub transaction_presence_args: TransactionPresenceEvaluatorArgs,
+
+    #[clap(flatten)]
+    pub version_lag_args: VersionLagEvaluatorArgs,
 }
 
 #[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
@@ -195,6 +209,27 @@ pub struct NodeAddress {
     #[oai(default = "Self::default_noise_port")]
     #[serde(default = "NodeAddress::default_noise_port")]
     pub noise_port: u16,
+
+    /// Extra headers to send with requests to the API index endpoint, for
+    /// nodes that sit behind a gateway. Each header is given as name:value.
+    #[clap(long = "api-header", parse(try_from_str = parse_api_header))]
+    #[oai(skip)]
+    #[serde(default)]
+    pub api_headers: Vec<(String, String)>,
+
+    /// Bearer token to send in the Authorization header of requests to the
+    /// API index endpoint.
+    #[clap(long)]
+    #[oai(skip)]
+    #[serde(default)]
+    pub api_bearer_token: Option<String>,
+}
+
+fn parse_api_header(header: &str) -> Result<(String, String)> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| format_err!("Header must be of the form name:value, got {}", header))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
 }
 
 impl NodeAddress {
@@ -215,6 +250,17 @@ impl NodeAddress {
         url.set_port(Some(self.api_port)).unwrap();
         url
     }
+
+    /// Add the configured headers and bearer token, if any, to a request.
+    pub fn add_api_auth(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.api_headers {
+            request = request.header(name, value);
+        }
+        match self.api_bearer_token.as_deref() {
+            Some(token) if !token.is_empty() => request.bearer_auth(token),
+            _ => request,
+        }
+    }
 }
 
 impl Example for NodeAddress {
@@ -224,6 +270,8 @@ impl Example for NodeAddress {
             metrics_port: Self::default_metrics_port(),
             api_port: Self::default_api_port(),
             noise_port: Self::default_noise_port(),
+            api_headers: vec![],
+            api_bearer_token: None,
         }
     }
 }
@@ -6,8 +6,9 @@ use crate::{
     evaluator::Evaluator,
     evaluators::{
         direct::{
-            ApiEvaluatorError, DirectEvaluatorInput, LatencyEvaluator, TpsEvaluator,
-            TpsEvaluatorError, TransactionPresenceEvaluator,
+            ApiBuildVersionEvaluator, ApiEvaluatorError, DirectEvaluatorInput, EpochMatchEvaluator,
+            LatencyEvaluator, TpsEvaluator, TpsEvaluatorError, TransactionPresenceEvaluator,
+            VersionLagEvaluator,
         },
         metrics::{
             ConsensusProposalsEvaluator, ConsensusRoundEvaluator, ConsensusTimeoutsEvaluator,
@@ -98,6 +99,11 @@ pub fn build_evaluators(
         evaluator_identifiers.iter().cloned().collect();
     let mut evaluators: Vec<EvaluatorType> = vec![];
 
+    ApiBuildVersionEvaluator::add_from_evaluator_args(
+        &mut evaluators,
+        &mut evaluator_identifiers,
+        evaluator_args,
+    )?;
     BuildVersionEvaluator::add_from_evaluator_args(
         &mut evaluators,
         &mut evaluator_identifiers,
@@ -118,6 +124,11 @@ pub fn build_evaluators(
         &mut evaluator_identifiers,
         evaluator_args,
     )?;
+    EpochMatchEvaluator::add_from_evaluator_args(
+        &mut evaluators,
+        &mut evaluator_identifiers,
+        evaluator_args,
+    )?;
     LatencyEvaluator::add_from_evaluator_args(
         &mut evaluators,
         &mut evaluator_identifiers,
@@ -148,6 +159,11 @@ pub fn build_evaluators(
         &mut evaluator_identifiers,
         evaluator_args,
     )?;
+    VersionLagEvaluator::add_from_evaluator_args(
+        &mut evaluators,
+        &mut evaluator_identifiers,
+        evaluator_args,
+    )?;
 
     if !evaluator_identifiers.is_empty() {
         bail!(
@@ -0,0 +1,198 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    super::DirectEvaluatorInput, node_identity::get_index_response, ApiEvaluatorError,
+    NodeIdentityEvaluatorArgs, API_CATEGORY,
+};
+use crate::{
+    configuration::EvaluatorArgs,
+    evaluator::{EvaluationResult, Evaluator},
+    evaluators::EvaluatorType,
+};
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use poem_openapi::Object as PoemObject;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const GIT_HASH_KEY: &str = "git_hash";
+
+/// Older nodes don't include the git hash in the IndexResponse and some
+/// builds report an empty one, both of which we treat as unknown.
+fn get_git_hash(data: &HashMap<String, serde_json::Value>) -> Option<String> {
+    data.get(GIT_HASH_KEY)
+        .and_then(|value| value.as_str())
+        .filter(|git_hash| !git_hash.is_empty())
+        .map(|git_hash| git_hash.to_string())
+}
+
+#[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
+pub struct ApiBuildVersionEvaluatorArgs {}
+
+/// This evaluator compares the git hash reported in the IndexResponse of the
+/// target node against the one reported by the baseline node. It uses the
+/// same request settings as the node identity evaluator.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct ApiBuildVersionEvaluator {
+    args: ApiBuildVersionEvaluatorArgs,
+    node_identity_args: NodeIdentityEvaluatorArgs,
+}
+
+impl ApiBuildVersionEvaluator {
+    pub fn new(
+        args: ApiBuildVersionEvaluatorArgs,
+        node_identity_args: NodeIdentityEvaluatorArgs,
+    ) -> Self {
+        Self {
+            args,
+            node_identity_args,
+        }
+    }
+
+    /// If either node doesn't report a git hash we can't say anything about
+    /// whether the builds are compatible, so we don't produce a result rather
+    /// than penalizing the target.
+    fn compare_git_hashes(
+        &self,
+        baseline_git_hash: Option<String>,
+        target_git_hash: Option<String>,
+    ) -> Vec<EvaluationResult> {
+        let (baseline_git_hash, target_git_hash) = match (baseline_git_hash, target_git_hash) {
+            (Some(baseline_git_hash), Some(target_git_hash)) => {
+                (baseline_git_hash, target_git_hash)
+            }
+            _ => {
+                debug!("Not evaluating API build version because the git hash is unknown");
+                return vec![];
+            }
+        };
+
+        let evaluation_result = if baseline_git_hash == target_git_hash {
+            self.build_evaluation_result(
+                "Build git hashes match".to_string(),
+                100,
+                format!(
+                    "The git hash reported by the API of the target node ({}) matches \
+                    the git hash reported by the baseline node ({}).",
+                    target_git_hash, baseline_git_hash
+                ),
+            )
+        } else {
+            self.build_evaluation_result(
+                "Build git hash mismatch".to_string(),
+                50,
+                format!(
+                    "The git hash reported by the API of the target node ({}) does not \
+                    match the git hash reported by the baseline node ({}). Make sure \
+                    you're running a build compatible with the baseline.",
+                    target_git_hash, baseline_git_hash
+                ),
+            )
+        };
+        vec![evaluation_result]
+    }
+}
+
+#[async_trait::async_trait]
+impl Evaluator for ApiBuildVersionEvaluator {
+    type Input = DirectEvaluatorInput;
+    type Error = ApiEvaluatorError;
+
+    /// Assert that the git hashes reported by the two nodes match.
+    async fn evaluate(&self, input: &Self::Input) -> Result<Vec<EvaluationResult>, Self::Error> {
+        let baseline_data = get_index_response(
+            &input.baseline_node_information.node_address,
+            &self.node_identity_args,
+        )
+        .await
+        .map_err(|e| ApiEvaluatorError::EndpointError("/".to_string(), e.into()))?;
+
+        let target_data =
+            match get_index_response(&input.target_node_address, &self.node_identity_args).await {
+                Ok(data) => data,
+                Err(e) => {
+                    return Ok(vec![self.build_evaluation_result(
+                        "Failed to get build information from target node".to_string(),
+                        0,
+                        format!(
+                            "Failed to get the index response from the target node, make \
+                            sure your API port ({}) is open: {}",
+                            input.target_node_address.api_port, e
+                        ),
+                    )]);
+                }
+            };
+
+        Ok(self.compare_git_hashes(get_git_hash(&baseline_data), get_git_hash(&target_data)))
+    }
+
+    fn get_category_name() -> String {
+        API_CATEGORY.to_string()
+    }
+
+    fn get_evaluator_name() -> String {
+        "build_version".to_string()
+    }
+
+    fn from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<Self> {
+        Ok(Self::new(
+            evaluator_args.api_build_version_args.clone(),
+            evaluator_args.node_identity_args.clone(),
+        ))
+    }
+
+    fn evaluator_type_from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<EvaluatorType> {
+        Ok(EvaluatorType::Api(Box::new(Self::from_evaluator_args(
+            evaluator_args,
+        )?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn get_evaluator() -> ApiBuildVersionEvaluator {
+        ApiBuildVersionEvaluator::new(
+            ApiBuildVersionEvaluatorArgs {},
+            NodeIdentityEvaluatorArgs::parse_from(&["test"]),
+        )
+    }
+
+    fn test_compare(baseline: Option<&str>, target: Option<&str>, expected_score: Option<u8>) {
+        let evaluations = get_evaluator().compare_git_hashes(
+            baseline.map(|v| v.to_string()),
+            target.map(|v| v.to_string()),
+        );
+        assert_eq!(evaluations.first().map(|e| e.score), expected_score);
+    }
+
+    #[test]
+    fn test_same() {
+        test_compare(Some("aaaaaaaaaa"), Some("aaaaaaaaaa"), Some(100));
+    }
+
+    #[test]
+    fn test_different() {
+        test_compare(Some("aaaaaaaaaa"), Some("bbbbbbbbbb"), Some(50));
+    }
+
+    #[test]
+    fn test_unknown() {
+        test_compare(Some("aaaaaaaaaa"), None, None);
+        test_compare(None, Some("aaaaaaaaaa"), None);
+    }
+
+    #[test]
+    fn test_get_git_hash() {
+        let mut data = HashMap::new();
+        assert_eq!(get_git_hash(&data), None);
+        data.insert(GIT_HASH_KEY.to_string(), serde_json::json!(""));
+        assert_eq!(get_git_hash(&data), None);
+        data.insert(GIT_HASH_KEY.to_string(), serde_json::json!("aaaaaaaaaa"));
+        assert_eq!(get_git_hash(&data), Some("aaaaaaaaaa".to_string()));
+    }
+}
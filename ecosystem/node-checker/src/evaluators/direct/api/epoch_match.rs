@@ -0,0 +1,207 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    super::DirectEvaluatorInput,
+    node_identity::{get_index_response, get_u64_field},
+    ApiEvaluatorError, NodeIdentityEvaluatorArgs, API_CATEGORY,
+};
+use crate::{
+    configuration::EvaluatorArgs,
+    evaluator::{EvaluationResult, Evaluator},
+    evaluators::EvaluatorType,
+};
+use anyhow::{format_err, Result};
+use clap::Parser;
+use poem_openapi::Object as PoemObject;
+use serde::{Deserialize, Serialize};
+use tokio::try_join;
+
+const EPOCH_KEY: &str = "epoch";
+
+#[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
+pub struct EpochMatchEvaluatorArgs {
+    /// How many epochs the target may be behind the baseline while it is
+    /// catching up. Within this range the score decreases with every epoch
+    /// the target is behind, beyond it the evaluation fails.
+    #[clap(long, default_value_t = 2)]
+    pub max_epoch_lag: u64,
+}
+
+#[derive(Debug)]
+pub struct EpochMatchEvaluator {
+    args: EpochMatchEvaluatorArgs,
+    node_identity_args: NodeIdentityEvaluatorArgs,
+}
+
+impl EpochMatchEvaluator {
+    pub fn new(
+        args: EpochMatchEvaluatorArgs,
+        node_identity_args: NodeIdentityEvaluatorArgs,
+    ) -> Self {
+        Self {
+            args,
+            node_identity_args,
+        }
+    }
+
+    fn help_build_evaluation_result(
+        &self,
+        baseline_epoch: u64,
+        target_epoch: u64,
+    ) -> EvaluationResult {
+        let lag = baseline_epoch.saturating_sub(target_epoch);
+        let (headline, score, explanation) = if lag == 0 {
+            (
+                "Epoch matches the baseline".to_string(),
+                100,
+                format!(
+                    "The node under investigation is in epoch {}, which is not behind \
+                    the baseline node (epoch {})",
+                    target_epoch, baseline_epoch
+                ),
+            )
+        } else if lag <= self.args.max_epoch_lag {
+            // Go from 100 towards 50 as the target falls further behind.
+            let score = 100 - (lag * 50 / self.args.max_epoch_lag) as u8;
+            (
+                "Epoch is behind the baseline".to_string(),
+                score,
+                format!(
+                    "The node under investigation is in epoch {}, which is {} epoch(s) \
+                    behind the baseline node (epoch {}). This is expected while the node \
+                    is catching up, but it should converge on the baseline epoch soon.",
+                    target_epoch, lag, baseline_epoch
+                ),
+            )
+        } else {
+            (
+                "Epoch is too far behind the baseline".to_string(),
+                0,
+                format!(
+                    "The node under investigation is in epoch {}, which is {} epochs \
+                    behind the baseline node (epoch {}). The maximum allowed gap is {} \
+                    epoch(s). Make sure your node is syncing and is on the same chain \
+                    as the baseline.",
+                    target_epoch, lag, baseline_epoch, self.args.max_epoch_lag
+                ),
+            )
+        };
+        self.build_evaluation_result(headline, score, explanation)
+    }
+}
+
+#[async_trait::async_trait]
+impl Evaluator for EpochMatchEvaluator {
+    type Input = DirectEvaluatorInput;
+    type Error = ApiEvaluatorError;
+
+    /// Assert that the target is in the same epoch as the baseline, or close to it.
+    async fn evaluate(&self, input: &Self::Input) -> Result<Vec<EvaluationResult>, Self::Error> {
+        // Failures from the target are turned into evaluation results below,
+        // so only a baseline failure should cut the other request short.
+        let baseline_future = async {
+            get_index_response(
+                &input.baseline_node_information.node_address,
+                &self.node_identity_args,
+            )
+            .await
+            .map_err(|e| ApiEvaluatorError::EndpointError("/".to_string(), e.into()))
+            .and_then(|data| {
+                get_u64_field(&data, EPOCH_KEY).ok_or_else(|| {
+                    ApiEvaluatorError::EndpointError(
+                        "/".to_string(),
+                        format_err!("Baseline index response is missing {}", EPOCH_KEY),
+                    )
+                })
+            })
+        };
+        let target_future = async {
+            Ok::<_, ApiEvaluatorError>(
+                get_index_response(&input.target_node_address, &self.node_identity_args).await,
+            )
+        };
+        let (baseline_epoch, target_data) = try_join!(baseline_future, target_future)?;
+
+        let target_epoch = match target_data.map(|data| get_u64_field(&data, EPOCH_KEY)) {
+            Ok(Some(epoch)) => epoch,
+            Ok(None) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Epoch missing from target node".to_string(),
+                    0,
+                    format!(
+                        "The index response from the target node did not contain a valid {}.",
+                        EPOCH_KEY
+                    ),
+                )]);
+            }
+            Err(e) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Failed to get epoch from target node".to_string(),
+                    0,
+                    format!(
+                        "Failed to get the index response from the target node, make \
+                        sure your API port ({}) is open: {}",
+                        input.target_node_address.api_port, e
+                    ),
+                )]);
+            }
+        };
+
+        Ok(vec![self.help_build_evaluation_result(
+            baseline_epoch,
+            target_epoch,
+        )])
+    }
+
+    fn get_category_name() -> String {
+        API_CATEGORY.to_string()
+    }
+
+    fn get_evaluator_name() -> String {
+        "epoch_match".to_string()
+    }
+
+    fn from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<Self> {
+        Ok(Self::new(
+            evaluator_args.epoch_match_args.clone(),
+            evaluator_args.node_identity_args.clone(),
+        ))
+    }
+
+    fn evaluator_type_from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<EvaluatorType> {
+        Ok(EvaluatorType::Api(Box::new(Self::from_evaluator_args(
+            evaluator_args,
+        )?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_evaluator(baseline_epoch: u64, target_epoch: u64, expected_score: u8) {
+        let evaluator = EpochMatchEvaluator::new(
+            EpochMatchEvaluatorArgs { max_epoch_lag: 2 },
+            NodeIdentityEvaluatorArgs::parse_from(&["test"]),
+        );
+        let evaluation = evaluator.help_build_evaluation_result(baseline_epoch, target_epoch);
+        assert_eq!(evaluation.score, expected_score);
+    }
+
+    #[test]
+    fn test_same_epoch() {
+        test_evaluator(10, 10, 100);
+    }
+
+    #[test]
+    fn test_catching_up() {
+        test_evaluator(10, 9, 75);
+        test_evaluator(10, 8, 50);
+    }
+
+    #[test]
+    fn test_too_far_behind() {
+        test_evaluator(10, 7, 0);
+    }
+}
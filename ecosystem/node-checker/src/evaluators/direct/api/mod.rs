@@ -1,17 +1,23 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod build_version;
+mod epoch_match;
 mod latency;
 mod node_identity;
 mod transaction_presence;
+mod version_lag;
 
 use anyhow::Error;
+pub use build_version::{ApiBuildVersionEvaluator, ApiBuildVersionEvaluatorArgs};
+pub use epoch_match::{EpochMatchEvaluator, EpochMatchEvaluatorArgs};
 pub use latency::{LatencyEvaluator, LatencyEvaluatorArgs};
 pub use node_identity::{
     get_node_identity, NodeIdentityEvaluator, NodeIdentityEvaluatorArgs, NodeIdentityEvaluatorError,
 };
 use thiserror::Error as ThisError;
 pub use transaction_presence::{TransactionPresenceEvaluator, TransactionPresenceEvaluatorArgs};
+pub use version_lag::{VersionLagEvaluator, VersionLagEvaluatorArgs};
 
 pub const API_CATEGORY: &str = "api";
 
@@ -6,74 +6,289 @@ use crate::{
     evaluator::{EvaluationResult, Evaluator},
     evaluators::EvaluatorType,
 };
-use anyhow::{anyhow, format_err, Result};
+use anyhow::Result;
 use aptos_config::config::RoleType;
 use aptos_sdk::types::chain_id::ChainId;
 use clap::Parser;
 use poem_openapi::Object as PoemObject;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{
+    collections::HashMap, error::Error, fmt::Display, path::PathBuf, str::FromStr, time::Duration,
+};
 use thiserror::Error as ThisError;
+use tokio::join;
+use url::Url;
 
 use super::{super::DirectEvaluatorInput, API_CATEGORY};
 
-/// This function hits the `/` endpoint of the API and returns the chain ID
-/// and role type, extracted from the IndexResponse.
-pub async fn get_node_identity(node_address: &NodeAddress) -> Result<(ChainId, RoleType)> {
-    let mut url = node_address.url.clone();
-    url.set_port(Some(node_address.api_port))
-        .map_err(|_| format_err!("Failed to set port for URL"))?;
-
-    let client = reqwest::ClientBuilder::new()
-        .timeout(std::time::Duration::from_secs(4))
-        .build()
-        .unwrap();
+/// The longest we wait between two attempts, however many there are.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
 
-    let response = client
-        .get(url)
+/// Fetch the body of the `/` endpoint. Responses with an error status are
+/// turned into errors so the caller can decide whether to retry them.
+async fn fetch_node_identity_body(
+    client: &reqwest::Client,
+    node_address: &NodeAddress,
+    url: Url,
+) -> reqwest::Result<String> {
+    node_address
+        .add_api_auth(client.get(url))
         .send()
-        .await
-        .map_err(|e| format_err!("Failed to get node identity {}", e))?;
-    let response_body = response
+        .await?
+        .error_for_status()?
         .text()
         .await
-        .map_err(|e| format_err!("Failed to get body of node identity response {}", e))?;
+}
 
-    let data: HashMap<String, serde_json::Value> =
-        serde_json::from_str(&response_body).map_err(|e| {
-            format_err!(
-                "Failed to process response body as valid JSON with string key/values {}",
+/// Build a description of the error including all of its sources, since the
+/// top level reqwest error doesn't say why the request failed.
+fn describe_error_chain(error: &reqwest::Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        description.push_str(": ");
+        description.push_str(&e.to_string());
+        source = e.source();
+    }
+    description
+}
+
+/// reqwest doesn't expose TLS failures as a distinct kind of error, so we
+/// look for them in the source chain of connection errors.
+fn is_tls_error(error: &reqwest::Error) -> bool {
+    if !error.is_connect() {
+        return false;
+    }
+    let description = describe_error_chain(error).to_lowercase();
+    ["certificate", "tls", "ssl", "handshake"]
+        .iter()
+        .any(|needle| description.contains(needle))
+}
+
+/// Network errors and server errors are worth retrying, client errors are not.
+/// TLS errors won't go away by themselves either, so we don't retry those.
+fn is_retryable(error: &reqwest::Error) -> bool {
+    !is_tls_error(error)
+        && error
+            .status()
+            .map_or(true, |status| status.is_server_error())
+}
+
+fn build_client(
+    args: &NodeIdentityEvaluatorArgs,
+) -> Result<reqwest::Client, NodeIdentityEvaluatorError> {
+    let mut builder = reqwest::ClientBuilder::new()
+        .timeout(args.timeout())
+        .danger_accept_invalid_certs(args.node_identity_accept_invalid_certs);
+    if let Some(ca_cert_path) = &args.node_identity_ca_cert_path {
+        let pem = std::fs::read(ca_cert_path).map_err(|e| {
+            NodeIdentityEvaluatorError::TlsConfigError(format!(
+                "Failed to read CA certificate {}: {}",
+                ca_cert_path.display(),
                 e
-            )
+            ))
+        })?;
+        let certificate = reqwest::Certificate::from_pem(&pem).map_err(|e| {
+            NodeIdentityEvaluatorError::TlsConfigError(format!(
+                "Failed to parse CA certificate {}: {}",
+                ca_cert_path.display(),
+                e
+            ))
         })?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder
+        .build()
+        .map_err(|e| NodeIdentityEvaluatorError::TlsConfigError(describe_error_chain(&e)))
+}
+
+/// This function hits the `/` endpoint of the API and returns the
+/// IndexResponse as a map. Requests that fail due to network or server
+/// errors are retried according to `args`. The scheme of the node URL is
+/// kept as is, so https works too. If the final attempt times out, a
+/// `NodeIdentityEvaluatorError::Timeout` is returned, whereas if the TLS
+/// handshake fails a `NodeIdentityEvaluatorError::TlsError` is returned.
+pub async fn get_index_response(
+    node_address: &NodeAddress,
+    args: &NodeIdentityEvaluatorArgs,
+) -> Result<HashMap<String, serde_json::Value>, NodeIdentityEvaluatorError> {
+    let mut url = node_address.url.clone();
+    url.set_port(Some(node_address.api_port)).map_err(|_| {
+        NodeIdentityEvaluatorError::ParseError("Failed to set port for URL".to_string())
+    })?;
 
-    let chain_id_raw: u8 = data
-        .get("chain_id")
-        .ok_or_else(|| format_err!("Failed to get chain_id from node identity"))?
-        .as_u64()
-        .ok_or_else(|| anyhow!("Failed to read chain ID from node identity as u8"))?
-        as u8;
+    let client = build_client(args)?;
+
+    let max_attempts = args.node_identity_max_attempts.max(1);
+    let mut backoff = std::cmp::min(
+        Duration::from_millis(args.node_identity_retry_backoff_ms),
+        MAX_RETRY_BACKOFF,
+    );
+    let mut attempt = 1;
+    let response_body = loop {
+        match fetch_node_identity_body(&client, node_address, url.clone()).await {
+            Ok(body) => break body,
+            Err(e) if attempt < max_attempts && is_retryable(&e) => {
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff.saturating_mul(2), MAX_RETRY_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) if e.is_timeout() => {
+                return Err(NodeIdentityEvaluatorError::Timeout(
+                    args.node_identity_timeout_secs,
+                    attempt,
+                ));
+            }
+            Err(e) if is_tls_error(&e) => {
+                return Err(NodeIdentityEvaluatorError::TlsError(describe_error_chain(
+                    &e,
+                )));
+            }
+            Err(e) => return Err(NodeIdentityEvaluatorError::NetworkError(attempt, e)),
+        }
+    };
+
+    serde_json::from_str(&response_body).map_err(NodeIdentityEvaluatorError::MalformedResponse)
+}
+
+/// The API serializes most u64s as strings, so accept both strings and
+/// plain numbers when reading them out of the IndexResponse.
+pub fn get_u64_field(data: &HashMap<String, serde_json::Value>, field: &str) -> Option<u64> {
+    match data.get(field)? {
+        serde_json::Value::String(value) => value.parse().ok(),
+        value => value.as_u64(),
+    }
+}
+
+fn get_field<'a>(
+    data: &'a HashMap<String, serde_json::Value>,
+    field: &str,
+) -> Result<&'a serde_json::Value, NodeIdentityEvaluatorError> {
+    data.get(field)
+        .ok_or_else(|| NodeIdentityEvaluatorError::MissingField(field.to_string()))
+}
+
+/// This function hits the `/` endpoint of the API and returns the chain ID
+/// and role type, extracted from the IndexResponse.
+pub async fn get_node_identity(
+    node_address: &NodeAddress,
+    args: &NodeIdentityEvaluatorArgs,
+) -> Result<(ChainId, RoleType), NodeIdentityEvaluatorError> {
+    let data = get_index_response(node_address, args).await?;
+
+    let chain_id_raw: u8 = get_field(&data, "chain_id")?.as_u64().ok_or_else(|| {
+        NodeIdentityEvaluatorError::ParseError(
+            "Failed to read chain ID from node identity as u8".to_string(),
+        )
+    })? as u8;
     let chain_id = ChainId::new(chain_id_raw);
 
-    let role_type_raw = data
-        .get("node_role")
-        .ok_or_else(|| format_err!("Failed to get node_role from node identity"))?
-        .as_str()
-        .ok_or_else(|| anyhow!("Failed to read node_role from node identity as str"))?;
-    let role_type = RoleType::from_str(role_type_raw)
-        .map_err(|e| format_err!("Failed to parse node_role {}", e))?;
+    let role_type_raw = get_field(&data, "node_role")?.as_str().ok_or_else(|| {
+        NodeIdentityEvaluatorError::ParseError(
+            "Failed to read node_role from node identity as str".to_string(),
+        )
+    })?;
+    let role_type = RoleType::from_str(role_type_raw).map_err(|e| {
+        NodeIdentityEvaluatorError::ParseError(format!("Failed to parse node_role {}", e))
+    })?;
 
     Ok((chain_id, role_type))
 }
 
 #[derive(Debug, ThisError)]
-pub enum NodeIdentityEvaluatorError {}
+pub enum NodeIdentityEvaluatorError {
+    /// The request didn't complete within the configured timeout.
+    #[error("Request for node identity timed out after {0} seconds ({1} attempt(s))")]
+    Timeout(u64, u16),
+
+    /// The TLS handshake with the node failed, e.g. because it uses a
+    /// self-signed certificate.
+    #[error("TLS error while getting node identity: {0}")]
+    TlsError(String),
+
+    /// The TLS options for the client are invalid, e.g. the CA certificate
+    /// couldn't be read.
+    #[error("Invalid TLS configuration: {0}")]
+    TlsConfigError(String),
+
+    /// The request failed for a reason other than a timeout, e.g. the
+    /// connection was refused or the node returned an error status.
+    #[error("Failed to get node identity after {0} attempt(s): {1}")]
+    NetworkError(u16, #[source] reqwest::Error),
+
+    /// The response body was not valid JSON with string keys.
+    #[error("Failed to process response body as valid JSON with string key/values: {0}")]
+    MalformedResponse(#[source] serde_json::Error),
+
+    /// The response didn't contain the given field.
+    #[error("Failed to get {0} from node identity")]
+    MissingField(String),
+
+    /// A field was present but its value couldn't be parsed.
+    #[error("{0}")]
+    ParseError(String),
+}
 
 // TODO: Consider taking chain_id and role_type here instead.
 #[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
-pub struct NodeIdentityEvaluatorArgs {}
+pub struct NodeIdentityEvaluatorArgs {
+    /// How long to wait for the node to respond to the node identity request.
+    #[clap(long, default_value_t = NodeIdentityEvaluatorArgs::default_timeout_secs())]
+    #[serde(default = "NodeIdentityEvaluatorArgs::default_timeout_secs")]
+    pub node_identity_timeout_secs: u64,
+
+    /// How many times to try the node identity request before giving up.
+    /// Only network and server errors are retried.
+    #[clap(long, default_value_t = NodeIdentityEvaluatorArgs::default_max_attempts())]
+    #[serde(default = "NodeIdentityEvaluatorArgs::default_max_attempts")]
+    pub node_identity_max_attempts: u16,
+
+    /// How long to wait before the first retry. This doubles after every attempt,
+    /// up to 30 seconds.
+    #[clap(long, default_value_t = NodeIdentityEvaluatorArgs::default_retry_backoff_ms())]
+    #[serde(default = "NodeIdentityEvaluatorArgs::default_retry_backoff_ms")]
+    pub node_identity_retry_backoff_ms: u64,
+
+    /// The chain ID the target node is expected to report, e.g. mainnet or 1.
+    /// If set, this is checked independently of the baseline, which helps
+    /// catch cases where the wrong baseline configuration was selected.
+    #[clap(long)]
+    #[oai(skip)]
+    #[serde(default)]
+    pub expected_chain_id: Option<ChainId>,
+
+    /// Accept invalid TLS certificates, e.g. self-signed ones, when fetching
+    /// the node identity. Only use this for nodes you trust.
+    #[clap(long)]
+    #[serde(default)]
+    pub node_identity_accept_invalid_certs: bool,
+
+    /// Path to a PEM encoded CA certificate to trust in addition to the system
+    /// roots when fetching the node identity, e.g. for nodes using an internal CA.
+    #[clap(long, parse(from_os_str))]
+    #[oai(skip)]
+    #[serde(default)]
+    pub node_identity_ca_cert_path: Option<PathBuf>,
+}
+
+impl NodeIdentityEvaluatorArgs {
+    fn default_timeout_secs() -> u64 {
+        4
+    }
+
+    fn default_max_attempts() -> u16 {
+        3
+    }
+
+    fn default_retry_backoff_ms() -> u64 {
+        500
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.node_identity_timeout_secs)
+    }
+}
 
-#[allow(dead_code)]
 #[derive(Debug)]
 pub struct NodeIdentityEvaluator {
     args: NodeIdentityEvaluatorArgs,
@@ -84,6 +299,40 @@ impl NodeIdentityEvaluator {
         Self { args }
     }
 
+    fn build_expected_chain_id_evaluation_result(
+        &self,
+        expected_chain_id: ChainId,
+        target_chain_id: ChainId,
+    ) -> EvaluationResult {
+        if expected_chain_id == target_chain_id {
+            self.build_evaluation_result(
+                "Chain ID matches the expected network".to_string(),
+                100,
+                format!(
+                    "The node under investigation reported chain ID {} ({}), \
+                which is the network you asked for",
+                    target_chain_id,
+                    target_chain_id.id()
+                ),
+            )
+        } else {
+            self.build_evaluation_result(
+                "Chain ID does not match the expected network".to_string(),
+                0,
+                format!(
+                    "The node under investigation reported chain ID {} ({}) but you \
+                asked for {} ({}). Make sure your node is configured with the genesis \
+                and waypoint for the network you intend to join, or select the \
+                baseline configuration for the network it is actually on.",
+                    target_chain_id,
+                    target_chain_id.id(),
+                    expected_chain_id,
+                    expected_chain_id.id()
+                ),
+            )
+        }
+    }
+
     fn help_build_evaluation_result<T: Display + PartialEq>(
         &self,
         baseline_value: T,
@@ -125,37 +374,79 @@ impl Evaluator for NodeIdentityEvaluator {
     type Error = NodeIdentityEvaluatorError;
 
     /// Assert that the node identity (role type and chain ID) of the two nodes match.
+    /// If the target can't be reached we return an evaluation result, whereas
+    /// if it returns a response we can't interpret we return the error itself.
     async fn evaluate(&self, input: &Self::Input) -> Result<Vec<EvaluationResult>, Self::Error> {
-        let (target_chain_id, target_role_type) =
-            match get_node_identity(&input.target_node_address).await {
-                Ok((chain_id, role_type)) => (chain_id, role_type),
-                Err(e) => {
-                    return Ok(vec![self.build_evaluation_result(
-                        "Failed to get node identity from target node".to_string(),
-                        0,
-                        format!(
-                            "Failed to get node identity from target node, \
+        // Fetch both identities at the same time. If the baseline can't be
+        // reached, fall back to the identity it reported when the baseline
+        // configuration was loaded, so the target is still evaluated.
+        let (baseline_node_identity, target_node_identity) = join!(
+            get_node_identity(&input.baseline_node_information.node_address, &self.args),
+            get_node_identity(&input.target_node_address, &self.args),
+        );
+        let (baseline_chain_id, baseline_role_type) = baseline_node_identity.unwrap_or((
+            input.baseline_node_information.chain_id,
+            input.baseline_node_information.role_type,
+        ));
+
+        let (target_chain_id, target_role_type) = match target_node_identity {
+            Ok((chain_id, role_type)) => (chain_id, role_type),
+            Err(NodeIdentityEvaluatorError::Timeout(_, attempts)) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Timed out getting node identity from target node".to_string(),
+                    0,
+                    format!(
+                        "The request to get the node identity from the target node \
+                        did not complete within the configured timeout of {} seconds \
+                        after {} attempt(s), make sure your API port ({}) is open and \
+                        reachable",
+                        self.args.node_identity_timeout_secs,
+                        attempts,
+                        input.target_node_address.api_port
+                    ),
+                )]);
+            }
+            Err(NodeIdentityEvaluatorError::TlsError(e)) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "TLS error getting node identity from target node".to_string(),
+                    0,
+                    format!(
+                        "The TLS handshake with the target node failed, make sure the \
+                        certificate served on your API port ({}) is valid for {}. If you \
+                        use a self-signed or internal CA certificate, the node checker \
+                        needs to be configured to trust it: {}",
+                        input.target_node_address.api_port, input.target_node_address.url, e
+                    ),
+                )]);
+            }
+            Err(e @ NodeIdentityEvaluatorError::NetworkError(..)) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Failed to get node identity from target node".to_string(),
+                    0,
+                    format!(
+                        "Failed to get node identity from target node, \
                         make sure your API port ({}) is open and you're running \
                         the correct node version: {}",
-                            input.target_node_address.api_port, e
-                        ),
-                    )])
-                }
-            };
-
-        let evaluation_results = vec![
-            self.help_build_evaluation_result(
-                input.baseline_node_information.chain_id,
-                target_chain_id,
-                "Chain ID",
-            ),
-            self.help_build_evaluation_result(
-                input.baseline_node_information.role_type,
-                target_role_type,
-                "Role Type",
-            ),
+                        input.target_node_address.api_port, e
+                    ),
+                )]);
+            }
+            // The node responded but we couldn't make sense of the response,
+            // surface the cause to the caller rather than a score.
+            Err(e) => return Err(e),
+        };
+
+        let mut evaluation_results = vec![
+            self.help_build_evaluation_result(baseline_chain_id, target_chain_id, "Chain ID"),
+            self.help_build_evaluation_result(baseline_role_type, target_role_type, "Role Type"),
         ];
 
+        if let Some(expected_chain_id) = self.args.expected_chain_id {
+            evaluation_results.push(
+                self.build_expected_chain_id_evaluation_result(expected_chain_id, target_chain_id),
+            );
+        }
+
         Ok(evaluation_results)
     }
 
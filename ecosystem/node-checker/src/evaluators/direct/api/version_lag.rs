@@ -0,0 +1,233 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    super::DirectEvaluatorInput,
+    node_identity::{get_index_response, get_u64_field},
+    ApiEvaluatorError, NodeIdentityEvaluatorArgs, API_CATEGORY,
+};
+use crate::{
+    configuration::EvaluatorArgs,
+    evaluator::{EvaluationResult, Evaluator},
+    evaluators::EvaluatorType,
+};
+use anyhow::{format_err, Result};
+use clap::Parser;
+use poem_openapi::Object as PoemObject;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::try_join;
+
+const LEDGER_VERSION_KEY: &str = "ledger_version";
+
+fn get_ledger_version(data: &HashMap<String, serde_json::Value>) -> Option<u64> {
+    get_u64_field(data, LEDGER_VERSION_KEY)
+}
+
+#[derive(Clone, Debug, Deserialize, Parser, PoemObject, Serialize)]
+pub struct VersionLagEvaluatorArgs {
+    /// If the target is further behind the baseline than this many versions,
+    /// it gets a partial score.
+    #[clap(long, default_value_t = 1000)]
+    pub version_lag_warning_threshold: u64,
+
+    /// If the target is further behind the baseline than this many versions,
+    /// it fails the evaluation.
+    #[clap(long, default_value_t = 10000)]
+    pub version_lag_failure_threshold: u64,
+}
+
+#[derive(Debug)]
+pub struct VersionLagEvaluator {
+    args: VersionLagEvaluatorArgs,
+    node_identity_args: NodeIdentityEvaluatorArgs,
+}
+
+impl VersionLagEvaluator {
+    pub fn new(
+        args: VersionLagEvaluatorArgs,
+        node_identity_args: NodeIdentityEvaluatorArgs,
+    ) -> Self {
+        Self {
+            args,
+            node_identity_args,
+        }
+    }
+
+    fn build_version_lag_evaluation(
+        &self,
+        baseline_version: u64,
+        target_version: u64,
+    ) -> EvaluationResult {
+        let lag = baseline_version.saturating_sub(target_version);
+        if lag > self.args.version_lag_failure_threshold {
+            self.build_evaluation_result(
+                "Ledger version is too far behind the baseline".to_string(),
+                0,
+                format!(
+                    "The target node is at ledger version {}, which is {} versions behind \
+                    the baseline node ({}). The maximum allowed lag is {} versions. Make \
+                    sure your node is syncing and has caught up.",
+                    target_version, lag, baseline_version, self.args.version_lag_failure_threshold
+                ),
+            )
+        } else if lag > self.args.version_lag_warning_threshold {
+            self.build_evaluation_result(
+                "Ledger version is behind the baseline".to_string(),
+                50,
+                format!(
+                    "The target node is at ledger version {}, which is {} versions behind \
+                    the baseline node ({}). This is more than the expected lag of {} \
+                    versions, but within the maximum of {}.",
+                    target_version,
+                    lag,
+                    baseline_version,
+                    self.args.version_lag_warning_threshold,
+                    self.args.version_lag_failure_threshold
+                ),
+            )
+        } else {
+            self.build_evaluation_result(
+                "Ledger version is caught up with the baseline".to_string(),
+                100,
+                format!(
+                    "The target node is at ledger version {}, which is within {} versions \
+                    of the baseline node ({}).",
+                    target_version, self.args.version_lag_warning_threshold, baseline_version
+                ),
+            )
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Evaluator for VersionLagEvaluator {
+    type Input = DirectEvaluatorInput;
+    type Error = ApiEvaluatorError;
+
+    /// Assert that the target's ledger version isn't too far behind the baseline's.
+    async fn evaluate(&self, input: &Self::Input) -> Result<Vec<EvaluationResult>, Self::Error> {
+        // Failures from the target are turned into evaluation results below,
+        // so only a baseline failure should cut the other request short.
+        let baseline_future = async {
+            get_index_response(
+                &input.baseline_node_information.node_address,
+                &self.node_identity_args,
+            )
+            .await
+            .map_err(|e| ApiEvaluatorError::EndpointError("/".to_string(), e.into()))
+            .and_then(|data| {
+                get_ledger_version(&data).ok_or_else(|| {
+                    ApiEvaluatorError::EndpointError(
+                        "/".to_string(),
+                        format_err!("Baseline index response is missing {}", LEDGER_VERSION_KEY),
+                    )
+                })
+            })
+        };
+        let target_future = async {
+            Ok::<_, ApiEvaluatorError>(
+                get_index_response(&input.target_node_address, &self.node_identity_args).await,
+            )
+        };
+        let (baseline_version, target_data) = try_join!(baseline_future, target_future)?;
+
+        let target_version = match target_data.map(|data| get_ledger_version(&data)) {
+            Ok(Some(version)) => version,
+            Ok(None) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Ledger version missing from target node".to_string(),
+                    0,
+                    format!(
+                        "The index response from the target node did not contain a valid {}.",
+                        LEDGER_VERSION_KEY
+                    ),
+                )]);
+            }
+            Err(e) => {
+                return Ok(vec![self.build_evaluation_result(
+                    "Failed to get ledger version from target node".to_string(),
+                    0,
+                    format!(
+                        "Failed to get the index response from the target node, make \
+                        sure your API port ({}) is open: {}",
+                        input.target_node_address.api_port, e
+                    ),
+                )]);
+            }
+        };
+
+        Ok(vec![self.build_version_lag_evaluation(
+            baseline_version,
+            target_version,
+        )])
+    }
+
+    fn get_category_name() -> String {
+        API_CATEGORY.to_string()
+    }
+
+    fn get_evaluator_name() -> String {
+        "version_lag".to_string()
+    }
+
+    fn from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<Self> {
+        Ok(Self::new(
+            evaluator_args.version_lag_args.clone(),
+            evaluator_args.node_identity_args.clone(),
+        ))
+    }
+
+    fn evaluator_type_from_evaluator_args(evaluator_args: &EvaluatorArgs) -> Result<EvaluatorType> {
+        Ok(EvaluatorType::Api(Box::new(Self::from_evaluator_args(
+            evaluator_args,
+        )?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn test_evaluator(baseline_version: u64, target_version: u64, expected_score: u8) {
+        let evaluator = VersionLagEvaluator::new(
+            VersionLagEvaluatorArgs {
+                version_lag_warning_threshold: 100,
+                version_lag_failure_threshold: 1000,
+            },
+            NodeIdentityEvaluatorArgs::parse_from(&["test"]),
+        );
+        let evaluation = evaluator.build_version_lag_evaluation(baseline_version, target_version);
+        assert_eq!(evaluation.score, expected_score);
+    }
+
+    #[test]
+    fn test_caught_up() {
+        test_evaluator(5000, 4950, 100);
+    }
+
+    #[test]
+    fn test_ahead_of_baseline() {
+        test_evaluator(5000, 5100, 100);
+    }
+
+    #[test]
+    fn test_behind() {
+        test_evaluator(5000, 4500, 50);
+    }
+
+    #[test]
+    fn test_too_far_behind() {
+        test_evaluator(5000, 3000, 0);
+    }
+
+    #[test]
+    fn test_get_ledger_version() {
+        let mut data = HashMap::new();
+        assert_eq!(get_ledger_version(&data), None);
+        data.insert(LEDGER_VERSION_KEY.to_string(), serde_json::json!("1234"));
+        assert_eq!(get_ledger_version(&data), Some(1234));
+        data.insert(LEDGER_VERSION_KEY.to_string(), serde_json::json!(1234));
+        assert_eq!(get_ledger_version(&data), Some(1234));
+    }
+}
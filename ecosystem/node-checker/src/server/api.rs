@@ -90,6 +90,8 @@ impl<M: MetricCollector, R: Runner> Api<M, R> {
             metrics_port: metrics_port.0,
             api_port: api_port.0,
             noise_port: noise_port.0,
+            api_headers: vec![],
+            api_bearer_token: None,
         };
         let request = CheckNodeRequest {
             baseline_configuration_name: baseline_configuration_name.0,
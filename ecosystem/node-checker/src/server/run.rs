@@ -74,6 +74,8 @@ pub async fn run(args: Run) -> Result<()> {
                 api_port: args.target_api_port,
                 metrics_port: args.target_metrics_port,
                 noise_port: args.target_noise_port,
+                api_headers: vec![],
+                api_bearer_token: None,
             };
             let metric_collector =
                 ReqwestMetricCollector::new(node_address.url.clone(), node_address.metrics_port);
@@ -1,17 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{ensure, format_err, Context, Result};
+use anyhow::{bail, ensure, format_err, Context, Result};
 use aptos_config::config::{RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_temppath::TempPath;
-use aptos_types::{transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    access_path::Path as AccessPathType,
+    state_store::state_key::StateKey,
+    transaction::{Transaction, Version},
+    waypoint::Waypoint,
+    write_set::WriteOp,
+};
 use aptos_vm::AptosVM;
 use aptosdb::{AptosDB, LEDGER_DB_NAME, STATE_MERKLE_DB_NAME};
-use executor::db_bootstrapper::calculate_genesis;
+use executor::db_bootstrapper::{calculate_genesis, GenesisCommitter};
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::Read,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 use storage_interface::DbReaderWriter;
 use structopt::StructOpt;
@@ -25,6 +33,7 @@ struct Opt {
     #[structopt(parse(from_os_str))]
     db_dir: PathBuf,
 
+    /// File with the BCS encoded genesis transaction, or "-" to read it from stdin.
     #[structopt(short, long, parse(from_os_str))]
     genesis_txn_file: PathBuf,
 
@@ -33,6 +42,41 @@ struct Opt {
 
     #[structopt(long, requires("waypoint-to-verify"))]
     commit: bool,
+
+    /// Warn instead of failing if the calculated waypoint doesn't match --waypoint-to-verify,
+    /// e.g. when a new waypoint is expected during disaster recovery. The waypoint version must
+    /// still match the number of transactions in the DB. Use with care.
+    #[structopt(long, requires("waypoint-to-verify"))]
+    force: bool,
+
+    /// Write the calculated waypoint to this file, whether or not it's committed.
+    #[structopt(long, parse(from_os_str))]
+    output_waypoint: Option<PathBuf>,
+
+    /// Format of the file written by --output-waypoint: "text" (the default) or "json".
+    #[structopt(long, requires("output-waypoint"))]
+    output_format: Option<OutputFormat>,
+
+    /// Print a summary of the state the genesis would write, compared to the current DB.
+    #[structopt(long)]
+    show_changes: bool,
+}
+
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => bail!("Invalid output format {}, expected text or json.", s),
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -81,6 +125,7 @@ fn main() -> Result<()> {
         )
     }
 
+    let base_version = tree_state.num_transactions.checked_sub(1);
     let committer = calculate_genesis::<AptosVM>(&db, tree_state, &genesis_txn)
         .with_context(|| format_err!("Failed to calculate genesis."))?;
     println!(
@@ -88,14 +133,30 @@ fn main() -> Result<()> {
         committer.waypoint()
     );
 
+    if opt.show_changes {
+        print_changes(&db, base_version, &committer)
+            .with_context(|| format_err!("Failed to summarize the genesis changes."))?;
+    }
+
+    if let Some(path) = &opt.output_waypoint {
+        let format = opt.output_format.as_ref().unwrap_or(&OutputFormat::Text);
+        write_waypoint(path, format, &committer.waypoint())
+            .with_context(|| format_err!("Failed to write waypoint to {:?}.", path))?;
+        println!("Waypoint written to {:?}.", path);
+    }
+
     if let Some(waypoint) = opt.waypoint_to_verify {
-        ensure!(
-            waypoint == committer.waypoint(),
-            "Waypoint verification failed. Expected {:?}, got {:?}.",
-            waypoint,
-            committer.waypoint(),
-        );
-        println!("Waypoint verified.");
+        if waypoint == committer.waypoint() {
+            println!("Waypoint verified.");
+        } else {
+            let msg = format!(
+                "Waypoint verification failed. Expected {:?}, got {:?}.",
+                waypoint,
+                committer.waypoint(),
+            );
+            ensure!(opt.force, "{}", msg);
+            eprintln!("WARNING: {} Continuing because of --force.", msg);
+        }
 
         if opt.commit {
             committer
@@ -108,10 +169,90 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum ChangeKind {
+    Added,
+    Changed,
+    Unchanged,
+    Deleted,
+}
+
+/// Prints what the genesis would write relative to the state at `base_version`, which is
+/// only read from the DB, so it's safe to use in the non-commit mode.
+fn print_changes(
+    db: &DbReaderWriter,
+    base_version: Option<Version>,
+    committer: &GenesisCommitter,
+) -> Result<()> {
+    let mut modules = vec![];
+    let mut resources = vec![];
+    let mut other_counts = BTreeMap::new();
+    for (state_key, write_op) in committer
+        .write_sets()
+        .flat_map(|write_set| write_set.iter())
+    {
+        let current = match base_version {
+            Some(version) => db
+                .reader
+                .get_state_value_by_version(state_key, version)?
+                .and_then(|state_value| state_value.maybe_bytes),
+            None => None,
+        };
+        let change = match (current, write_op) {
+            (None, WriteOp::Value(_)) => ChangeKind::Added,
+            (Some(current), WriteOp::Value(new)) if &current == new => ChangeKind::Unchanged,
+            (Some(_), WriteOp::Value(_)) => ChangeKind::Changed,
+            (Some(_), WriteOp::Deletion) => ChangeKind::Deleted,
+            (None, WriteOp::Deletion) => continue,
+        };
+        match state_key {
+            StateKey::AccessPath(access_path) => match access_path.get_path() {
+                AccessPathType::Code(module_id) => modules.push((change, module_id.to_string())),
+                AccessPathType::Resource(struct_tag) => {
+                    resources.push((change, format!("{} at {}", struct_tag, access_path.address)))
+                }
+            },
+            StateKey::TableItem { .. } => {
+                *other_counts.entry(("table items", change)).or_insert(0) += 1
+            }
+            StateKey::Raw(_) => *other_counts.entry(("raw keys", change)).or_insert(0) += 1,
+        }
+    }
+
+    for (title, mut entries) in [("Modules", modules), ("Resources", resources)] {
+        entries.sort();
+        println!("{} written by the genesis: {}", title, entries.len());
+        for (change, name) in entries {
+            println!("  {:?}: {}", change, name);
+        }
+    }
+    for ((title, change), count) in other_counts {
+        println!("{:?} {}: {}", change, title, count);
+    }
+    Ok(())
+}
+
+fn write_waypoint(path: &Path, format: &OutputFormat, waypoint: &Waypoint) -> Result<()> {
+    let contents = match format {
+        OutputFormat::Text => waypoint.to_string(),
+        OutputFormat::Json => {
+            serde_json::to_string(&serde_json::json!({ "waypoint": waypoint.to_string() }))?
+        }
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
 fn load_genesis_txn(path: &Path) -> Result<Transaction> {
-    let mut file = File::open(&path)?;
     let mut buffer = vec![];
-    file.read_to_end(&mut buffer)?;
+    if path == Path::new("-") {
+        std::io::stdin().read_to_end(&mut buffer)?;
+        ensure!(!buffer.is_empty(), "No genesis txn was provided on stdin.");
+    } else {
+        let mut file = File::open(&path)?;
+        file.read_to_end(&mut buffer)?;
+    }
 
-    Ok(bcs::from_bytes(&buffer)?)
+    bcs::from_bytes(&buffer)
+        .with_context(|| format_err!("Genesis txn is not a valid BCS Transaction."))
 }
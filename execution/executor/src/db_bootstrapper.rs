@@ -18,6 +18,7 @@ use aptos_types::{
     timestamp::TimestampResource,
     transaction::{Transaction, Version},
     waypoint::Waypoint,
+    write_set::WriteSet,
 };
 use aptos_vm::VMExecutor;
 use executor_types::{in_memory_state_calculator::IntoLedgerView, ExecutedChunk};
@@ -94,6 +95,14 @@ impl GenesisCommitter {
         self.waypoint
     }
 
+    /// The writes the genesis would make when committed, e.g. to inspect them beforehand.
+    pub fn write_sets(&self) -> impl Iterator<Item = &WriteSet> {
+        self.output
+            .to_commit
+            .iter()
+            .map(|(_, txn_data)| txn_data.write_set())
+    }
+
     pub fn commit(self) -> Result<()> {
         self.db.save_transactions(
             &self.output.transactions_to_commit()?,
@@ -263,7 +263,11 @@ impl EventSubscriptionService {
     fn read_on_chain_configs(&self, version: Version) -> Result<OnChainConfigPayload, Error> {
         // Build a map from config ID to the config value found on-chain
         let mut config_id_to_config = HashMap::new();
-        for config_id in self.config_registry.iter() {
+        let extra_configs: Vec<ConfigID> = on_chain_config::extra_configs()
+            .into_iter()
+            .filter(|config_id| !self.config_registry.contains(config_id))
+            .collect();
+        for config_id in self.config_registry.iter().chain(extra_configs.iter()) {
             if let Ok(config) = self
                 .storage
                 .read()
@@ -32,6 +32,7 @@ pub async fn setup_test(
         tls_cert_path: None,
         tls_key_path: None,
         content_length_limit: None,
+        cors_allowed_origins: vec![],
     };
 
     // Start the server
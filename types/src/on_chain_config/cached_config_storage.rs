@@ -0,0 +1,140 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    access_path::AccessPath,
+    on_chain_config::{
+        config_address, ConfigID, ConfigStorage, ConfigurationResource, OnChainConfig,
+    },
+};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Wraps a `ConfigStorage` and keeps the configs deserialized by `fetch_config` until the epoch
+/// advances. Configs only change on reconfiguration, so within an epoch the same value is
+/// returned without reading or deserializing the bytes again.
+pub struct CachedConfigStorage<S> {
+    storage: S,
+    cache: Mutex<EpochCache>,
+}
+
+#[derive(Default)]
+struct EpochCache {
+    epoch: u64,
+    configs: HashMap<ConfigID, Arc<dyn Any + Send + Sync>>,
+}
+
+impl<S: ConfigStorage> CachedConfigStorage<S> {
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            cache: Mutex::new(EpochCache::default()),
+        }
+    }
+
+    /// The current epoch according to the `ConfigurationResource`, or `None` if it can't be read.
+    pub fn current_epoch(&self) -> Option<u64> {
+        let access_path = AccessPath::resource_access_path(ResourceKey::new(
+            config_address(),
+            ConfigurationResource::struct_tag(),
+        ));
+        let bytes = self.storage.fetch_config(access_path)?;
+        bcs::from_bytes::<ConfigurationResource>(&bytes)
+            .ok()
+            .map(|resource| resource.epoch())
+    }
+
+    /// Same as `T::fetch_config`, but served from the cache if `T` has already been read in the
+    /// current epoch. The cache is flushed as soon as the epoch changes.
+    pub fn fetch_config<T: OnChainConfig + 'static>(&self) -> Option<Arc<T>> {
+        let epoch = self.current_epoch()?;
+        let mut cache = self.cache.lock().expect("config cache lock poisoned");
+        if cache.epoch != epoch {
+            cache.epoch = epoch;
+            cache.configs.clear();
+        }
+        if let Some(config) = cache.configs.get(&T::CONFIG_ID) {
+            return config.clone().downcast::<T>().ok();
+        }
+        let config = Arc::new(T::fetch_config(&self.storage)?);
+        cache.configs.insert(T::CONFIG_ID, config.clone());
+        Some(config)
+    }
+}
+
+impl<S: ConfigStorage> ConfigStorage for CachedConfigStorage<S> {
+    fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
+        self.storage.fetch_config(access_path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        event::EventHandle,
+        on_chain_config::{access_path_for_config, new_epoch_event_key, Version},
+    };
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockStorage {
+        values: RefCell<HashMap<AccessPath, Vec<u8>>>,
+        config_reads: RefCell<usize>,
+    }
+
+    impl MockStorage {
+        fn set_epoch(&self, epoch: u64) {
+            let resource = ConfigurationResource {
+                epoch,
+                last_reconfiguration_time: 0,
+                events: EventHandle::new(new_epoch_event_key(), epoch),
+            };
+            self.values.borrow_mut().insert(
+                AccessPath::resource_access_path(ResourceKey::new(
+                    config_address(),
+                    ConfigurationResource::struct_tag(),
+                )),
+                bcs::to_bytes(&resource).unwrap(),
+            );
+        }
+
+        fn set_version(&self, major: u64) {
+            self.values.borrow_mut().insert(
+                access_path_for_config(Version::CONFIG_ID),
+                bcs::to_bytes(&Version { major }).unwrap(),
+            );
+        }
+    }
+
+    impl ConfigStorage for &MockStorage {
+        fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
+            if access_path == access_path_for_config(Version::CONFIG_ID) {
+                *self.config_reads.borrow_mut() += 1;
+            }
+            self.values.borrow().get(&access_path).cloned()
+        }
+    }
+
+    #[test]
+    fn test_cache_is_flushed_on_new_epoch() {
+        let storage = MockStorage::default();
+        storage.set_epoch(1);
+        storage.set_version(3);
+        let cached = CachedConfigStorage::new(&storage);
+        assert_eq!(cached.current_epoch(), Some(1));
+
+        assert_eq!(cached.fetch_config::<Version>().unwrap().major, 3);
+        storage.set_version(4);
+        assert_eq!(cached.fetch_config::<Version>().unwrap().major, 3);
+        assert_eq!(*storage.config_reads.borrow(), 1);
+
+        storage.set_epoch(2);
+        assert_eq!(cached.fetch_config::<Version>().unwrap().major, 4);
+        assert_eq!(*storage.config_reads.borrow(), 2);
+    }
+}
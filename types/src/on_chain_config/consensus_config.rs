@@ -80,6 +80,11 @@ impl OnChainConfig for OnChainConsensusConfig {
         bcs::from_bytes(&raw_bytes)
             .map_err(|e| format_err!("[on-chain config] Failed to deserialize into config: {}", e))
     }
+
+    /// Inverse of `deserialize_into_config`, wraps the BCS bytes of the config in a `vector<u8>`.
+    fn serialize_into_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&bcs::to_bytes(self)?)?)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -211,6 +216,20 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_config_serialize_into_bytes_round_trip() {
+        let config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+            decoupled_execution: false,
+            ..ConsensusConfigV1::default()
+        });
+        let bytes = config.serialize_into_bytes().unwrap();
+
+        assert_eq!(
+            OnChainConsensusConfig::deserialize_into_config(&bytes).unwrap(),
+            config
+        );
+    }
+
     #[test]
     fn test_config_onchain_payload() {
         let consensus_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
@@ -0,0 +1,75 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+
+/// The feature flags known to this version of the node. The value of each variant is its bit
+/// index in `Features`.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeatureFlag {
+    CODE_DEPENDENCY_CHECK = 1,
+    TREAT_FRIEND_AS_PRIVATE = 2,
+    SHA_512_AND_RIPEMD_160_NATIVES = 3,
+    APTOS_STD_CHAIN_ID_NATIVES = 4,
+}
+
+/// The set of enabled feature flags, stored on chain as a bitset.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Features {
+    #[serde(with = "serde_bytes")]
+    pub features: Vec<u8>,
+}
+
+impl Features {
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        let index = flag as usize;
+        let byte_index = index / 8;
+        let bit_mask = 1 << (index % 8);
+        byte_index < self.features.len() && self.features[byte_index] & bit_mask != 0
+    }
+}
+
+impl OnChainConfig for Features {
+    const IDENTIFIER: &'static str = "Features";
+
+    /// The Move resource is
+    /// ```ignore
+    /// struct Features has key {
+    ///    features: vector<u8>,
+    /// }
+    /// ```
+    /// where bit `i % 8` of byte `i / 8` is set if the flag with index `i` is enabled. Bytes past
+    /// the end of the vector are all zero.
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        let features: Vec<u8> = bcs::from_bytes(bytes).map_err(|e| {
+            format_err!(
+                "[on-chain config] Failed to deserialize into Features: {}",
+                e
+            )
+        })?;
+        Ok(Features { features })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_feature_flags() {
+        let features = Features {
+            features: vec![0b0000_0110],
+        };
+        let bytes = features.serialize_into_bytes().unwrap();
+        let config = Features::deserialize_into_config(&bytes).unwrap();
+        assert_eq!(config, features);
+
+        assert!(!config.is_enabled(FeatureFlag::CODE_DEPENDENCY_CHECK));
+        assert!(config.is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE));
+        assert!(config.is_enabled(FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES));
+        assert!(!Features::default().is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE));
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use anyhow::{bail, format_err, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The gas schedule as a flat list of named parameters, as stored on chain.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct GasScheduleV2 {
+    pub entries: Vec<(String, u64)>,
+}
+
+impl GasScheduleV2 {
+    /// The parameters keyed by name.
+    pub fn to_btree_map(&self) -> BTreeMap<String, u64> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+impl OnChainConfig for GasScheduleV2 {
+    const IDENTIFIER: &'static str = "GasScheduleV2";
+
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        // Each entry is a Move `GasEntry { key: String, val: u64 }`, which has the same BCS
+        // layout as a `(String, u64)` tuple.
+        let gas_schedule = bcs::from_bytes::<Self>(bytes).map_err(|e| {
+            format_err!(
+                "[on-chain config] Failed to deserialize into GasScheduleV2: {}",
+                e
+            )
+        })?;
+        if gas_schedule.to_btree_map().len() != gas_schedule.entries.len() {
+            bail!("[on-chain config] GasScheduleV2 contains duplicate entries");
+        }
+        Ok(gas_schedule)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gas_schedule_deserialization() {
+        let gas_schedule = GasScheduleV2 {
+            entries: vec![
+                ("instr.add".to_string(), 3),
+                ("txn.min_transaction_gas_units".to_string(), 600),
+            ],
+        };
+        let bytes = bcs::to_bytes(&gas_schedule).unwrap();
+        let config = GasScheduleV2::deserialize_into_config(&bytes).unwrap();
+        assert_eq!(config, gas_schedule);
+        assert_eq!(config.to_btree_map().get("instr.add"), Some(&3));
+
+        let duplicated = GasScheduleV2 {
+            entries: vec![("instr.add".to_string(), 3), ("instr.add".to_string(), 4)],
+        };
+        let bytes = bcs::to_bytes(&duplicated).unwrap();
+        assert!(GasScheduleV2::deserialize_into_config(&bytes).is_err());
+    }
+}
@@ -4,21 +4,32 @@
 use crate::{
     access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::CORE_CODE_ADDRESS,
+    account_config::{NewEpochEvent, CORE_CODE_ADDRESS},
+    contract_event::ContractEvent,
     event::{EventHandle, EventKey},
+    transaction::TransactionOutput,
 };
-use anyhow::{format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use move_deps::move_core_types::{
     ident_str,
     identifier::{IdentStr, Identifier},
     language_storage::{StructTag, TypeTag},
     move_resource::{MoveResource, MoveStructType},
 };
+use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::{collections::HashMap, fmt, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+use thiserror::Error;
 
 mod aptos_version;
+mod cached_config_storage;
 mod consensus_config;
+mod features;
+mod gas_schedule;
 mod registered_currencies;
 mod validator_set;
 mod vm_config;
@@ -28,9 +39,12 @@ pub use self::{
     aptos_version::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
     },
+    cached_config_storage::CachedConfigStorage,
     consensus_config::{
         ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerElectionType,
     },
+    features::{FeatureFlag, Features},
+    gas_schedule::GasScheduleV2,
     registered_currencies::RegisteredCurrencies,
     validator_set::ValidatorSet,
     vm_config::VMConfig,
@@ -41,7 +55,7 @@ pub use self::{
 /// 1. Implement the `OnChainConfig` trait for the Rust representation of the config
 /// 2. Add the config's `ConfigID` to `ON_CHAIN_CONFIG_REGISTRY`
 
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct ConfigID(&'static str, &'static str, &'static str);
 
 pub const CONFIG_ADDRESS_STR: &str = "0xA550C18";
@@ -67,6 +81,9 @@ impl fmt::Display for ConfigID {
 }
 
 /// State sync will panic if the value of any config in this registry is uninitialized
+// TODO: `GasScheduleV2` belongs here, but genesis doesn't publish it yet and state sync would
+// panic on every existing chain. Until the framework does, it's read from the optional
+// `EXTRA_CONFIG_REGISTRY` instead.
 pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     VMConfig::CONFIG_ID,
     ValidatorSet::CONFIG_ID,
@@ -75,6 +92,42 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     OnChainConsensusConfig::CONFIG_ID,
 ];
 
+/// Configs registered at runtime on top of `ON_CHAIN_CONFIG_REGISTRY`, e.g. by tooling that reads
+/// the config resources of custom Move modules. Unlike the static registry, these are optional:
+/// a registered config that doesn't exist on chain is simply left out of the payload. It starts
+/// out with `GasScheduleV2`, see `ON_CHAIN_CONFIG_REGISTRY`.
+static EXTRA_CONFIG_REGISTRY: Lazy<RwLock<Vec<ConfigID>>> =
+    Lazy::new(|| RwLock::new(vec![GasScheduleV2::CONFIG_ID]));
+
+/// Add `config_id` to the configs read on each reconfiguration. Fails if it is already part of
+/// `ON_CHAIN_CONFIG_REGISTRY` or has been registered before.
+pub fn register_extra_config(config_id: ConfigID) -> Result<()> {
+    ensure!(
+        !ON_CHAIN_CONFIG_REGISTRY.contains(&config_id),
+        "[on-chain cfg] {} is already in the static registry",
+        config_id
+    );
+    let mut extra_configs = EXTRA_CONFIG_REGISTRY
+        .write()
+        .expect("extra config registry lock poisoned");
+    ensure!(
+        !extra_configs.contains(&config_id),
+        "[on-chain cfg] {} is already registered",
+        config_id
+    );
+    extra_configs.push(config_id);
+    Ok(())
+}
+
+/// The optional configs read on each reconfiguration: `GasScheduleV2`, then the configs added
+/// with `register_extra_config` in registration order.
+pub fn extra_configs() -> Vec<ConfigID> {
+    EXTRA_CONFIG_REGISTRY
+        .read()
+        .expect("extra config registry lock poisoned")
+        .clone()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct OnChainConfigPayload {
     epoch: u64,
@@ -90,17 +143,115 @@ impl OnChainConfigPayload {
         self.epoch
     }
 
-    pub fn get<T: OnChainConfig>(&self) -> Result<T> {
+    pub fn get<T: OnChainConfig>(&self) -> Result<T, ConfigError> {
         let bytes = self
             .configs
             .get(&T::CONFIG_ID)
-            .ok_or_else(|| format_err!("[on-chain cfg] config not in payload"))?;
+            .ok_or(ConfigError::NotInPayload(T::CONFIG_ID))?;
         T::deserialize_into_config(bytes)
+            .map_err(|e| ConfigError::Deserialize(T::CONFIG_ID, e.to_string()))
     }
 
     pub fn configs(&self) -> &HashMap<ConfigID, Vec<u8>> {
         &self.configs
     }
+
+    /// The configs whose serialized value differs between `self` and `other`, including the ones
+    /// present in only one of them, sorted by `ConfigID`.
+    pub fn diff(&self, other: &OnChainConfigPayload) -> Vec<ConfigID> {
+        let mut changed: Vec<ConfigID> = self
+            .configs
+            .iter()
+            .filter(|(config_id, bytes)| other.configs.get(config_id) != Some(bytes))
+            .map(|(config_id, _)| *config_id)
+            .chain(
+                other
+                    .configs
+                    .keys()
+                    .filter(|config_id| !self.configs.contains_key(config_id))
+                    .copied(),
+            )
+            .collect();
+        changed.sort();
+        changed
+    }
+
+    /// Attempt to deserialize every config in `registry` from this payload, collecting all
+    /// failures instead of stopping at the first one.
+    pub fn check_all(&self, registry: &[ConfigID]) -> ConfigCheckReport {
+        let mut report = ConfigCheckReport::default();
+        for config_id in registry {
+            let result = match self.configs.get(config_id) {
+                Some(bytes) => check_config_bytes(*config_id, bytes),
+                None => Err(format_err!("[on-chain cfg] config not in payload")),
+            };
+            match result {
+                Ok(()) => report.succeeded.push(*config_id),
+                Err(e) => report.failed.push((*config_id, e)),
+            }
+        }
+        report
+    }
+
+    /// Check that every config in `ON_CHAIN_CONFIG_REGISTRY` can be read from this payload, so
+    /// that a broken config is reported up front rather than as a panic in state sync.
+    pub fn validate_registry(&self) -> Result<(), Vec<ConfigError>> {
+        let errors: Vec<ConfigError> = ON_CHAIN_CONFIG_REGISTRY
+            .iter()
+            .filter_map(|config_id| match self.configs.get(config_id) {
+                Some(bytes) => check_config_bytes(*config_id, bytes)
+                    .err()
+                    .map(|e| ConfigError::Deserialize(*config_id, e.to_string())),
+                None => Some(ConfigError::NotInPayload(*config_id)),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Why a config couldn't be read from an `OnChainConfigPayload`.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum ConfigError {
+    #[error("[on-chain cfg] {0} not in payload")]
+    NotInPayload(ConfigID),
+    #[error("[on-chain cfg] failed to deserialize {0}: {1}")]
+    Deserialize(ConfigID, String),
+}
+
+/// The outcome of `OnChainConfigPayload::check_all`.
+#[derive(Debug, Default)]
+pub struct ConfigCheckReport {
+    pub succeeded: Vec<ConfigID>,
+    pub failed: Vec<(ConfigID, anyhow::Error)>,
+}
+
+impl ConfigCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Deserialize `bytes` into the Rust type registered for `config_id`, discarding the result.
+pub fn check_config_bytes(config_id: ConfigID, bytes: &[u8]) -> Result<()> {
+    fn check<T: OnChainConfig>(bytes: &[u8]) -> Result<()> {
+        T::deserialize_into_config(bytes).map(|_| ())
+    }
+
+    match config_id {
+        VMConfig::CONFIG_ID => check::<VMConfig>(bytes),
+        ValidatorSet::CONFIG_ID => check::<ValidatorSet>(bytes),
+        VMPublishingOption::CONFIG_ID => check::<VMPublishingOption>(bytes),
+        Version::CONFIG_ID => check::<Version>(bytes),
+        OnChainConsensusConfig::CONFIG_ID => check::<OnChainConsensusConfig>(bytes),
+        RegisteredCurrencies::CONFIG_ID => check::<RegisteredCurrencies>(bytes),
+        GasScheduleV2::CONFIG_ID => check::<GasScheduleV2>(bytes),
+        Features::CONFIG_ID => check::<Features>(bytes),
+        _ => bail!("[on-chain cfg] no known Rust type for {}", config_id),
+    }
 }
 
 impl fmt::Display for OnChainConfigPayload {
@@ -124,7 +275,7 @@ pub trait ConfigStorage {
 
 /// Trait to be implemented by a Rust struct representation of an on-chain config
 /// that is stored in storage as a serialized byte array
-pub trait OnChainConfig: Send + Sync + DeserializeOwned {
+pub trait OnChainConfig: Send + Sync + DeserializeOwned + Serialize {
     // aptos_root_address
     const ADDRESS: &'static str = CONFIG_ADDRESS_STR;
     const IDENTIFIER: &'static str;
@@ -150,6 +301,20 @@ pub trait OnChainConfig: Send + Sync + DeserializeOwned {
         Self::deserialize_default_impl(bytes)
     }
 
+    // Single-round BCS serialization of `Self`, the inverse of `deserialize_default_impl`
+    fn serialize_default_impl(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self)
+            .map_err(|e| format_err!("[on-chain config] Failed to serialize config: {}", e))
+    }
+
+    // Function for serializing `Self` into the bytes stored on chain, e.g. to build genesis or
+    // to override a config in a write set
+    // Types that override `deserialize_into_config` should override this function as well, so
+    // that `deserialize_into_config(&config.serialize_into_bytes()?)` gives back `config`
+    fn serialize_into_bytes(&self) -> Result<Vec<u8>> {
+        self.serialize_default_impl()
+    }
+
     fn fetch_config<T>(storage: &T) -> Option<Self>
     where
         T: ConfigStorage,
@@ -166,6 +331,31 @@ pub fn new_epoch_event_key() -> EventKey {
     EventKey::new_from_address(&config_address(), 5)
 }
 
+/// Whether `events` contain a `NewEpochEvent`, i.e. were emitted by a reconfiguration.
+pub fn contains_new_epoch_event(events: &[ContractEvent]) -> bool {
+    let new_epoch_event_key = new_epoch_event_key();
+    events
+        .iter()
+        .any(|event| *event.key() == new_epoch_event_key)
+}
+
+/// The epoch started by the first of `outputs` that reconfigures, if any.
+pub fn new_epoch_event_from_outputs(outputs: &[TransactionOutput]) -> Result<Option<u64>> {
+    let new_epoch_event_key = new_epoch_event_key();
+    for output in outputs {
+        if let Some(event) = output
+            .events()
+            .iter()
+            .find(|event| *event.key() == new_epoch_event_key)
+        {
+            return Ok(Some(
+                NewEpochEvent::try_from_bytes(event.event_data())?.epoch(),
+            ));
+        }
+    }
+    Ok(None)
+}
+
 pub fn struct_tag_for_config(config_name: Identifier) -> StructTag {
     StructTag {
         address: CORE_CODE_ADDRESS,
@@ -245,3 +435,53 @@ impl MoveStructType for ConfigurationResource {
 }
 
 impl MoveResource for ConfigurationResource {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transaction::{ExecutionStatus, TransactionStatus},
+        write_set::WriteSet,
+    };
+
+    fn output_with_events(events: Vec<ContractEvent>) -> TransactionOutput {
+        TransactionOutput::new(
+            WriteSet::default(),
+            events,
+            0,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+        )
+    }
+
+    fn new_epoch_event(epoch: u64) -> ContractEvent {
+        ContractEvent::new(
+            new_epoch_event_key(),
+            epoch,
+            TypeTag::Struct(NewEpochEvent::struct_tag()),
+            bcs::to_bytes(&epoch).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_new_epoch_event_detection() {
+        let other_event = ContractEvent::new(
+            EventKey::new_from_address(&config_address(), 0),
+            0,
+            TypeTag::U64,
+            bcs::to_bytes(&0u64).unwrap(),
+        );
+        assert!(!contains_new_epoch_event(&[other_event.clone()]));
+        assert!(contains_new_epoch_event(&[
+            other_event.clone(),
+            new_epoch_event(3)
+        ]));
+
+        let outputs = vec![
+            output_with_events(vec![other_event]),
+            output_with_events(vec![new_epoch_event(3)]),
+            output_with_events(vec![new_epoch_event(4)]),
+        ];
+        assert_eq!(new_epoch_event_from_outputs(&outputs[..1]).unwrap(), None);
+        assert_eq!(new_epoch_event_from_outputs(&outputs).unwrap(), Some(3));
+    }
+}
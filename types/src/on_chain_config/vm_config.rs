@@ -49,4 +49,16 @@ impl OnChainConfig for VMConfig {
         let gas_schedule = raw_vm_config.gas_schedule.as_cost_table()?;
         Ok(VMConfig { gas_schedule })
     }
+
+    /// The instruction and native tables are stored as opaque BCS bytes on chain.
+    fn serialize_into_bytes(&self) -> Result<Vec<u8>> {
+        let raw_vm_config = VMConfigInner {
+            gas_schedule: CostTableInner {
+                instruction_table: bcs::to_bytes(&self.gas_schedule.instruction_table)?,
+                native_table: bcs::to_bytes(&self.gas_schedule.native_table)?,
+                gas_constants: self.gas_schedule.gas_constants.clone(),
+            },
+        };
+        Ok(bcs::to_bytes(&raw_vm_config)?)
+    }
 }
@@ -5,6 +5,7 @@ mod access_path_test;
 mod block_metadata_test;
 mod code_debug_fmt_test;
 mod contract_event_test;
+mod on_chain_config_test;
 mod transaction_test;
 mod trusted_state_test;
 mod validator_set_test;
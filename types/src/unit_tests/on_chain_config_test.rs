@@ -0,0 +1,116 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::{
+    extra_configs, register_extra_config, ConfigError, GasScheduleV2, OnChainConfig,
+    OnChainConfigPayload, VMConfig, VMPublishingOption, ValidatorSet, Version,
+    ON_CHAIN_CONFIG_REGISTRY,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+
+#[test]
+fn test_check_all_reports_every_failure() {
+    let mut configs = HashMap::new();
+    configs.insert(
+        Version::CONFIG_ID,
+        bcs::to_bytes(&Version { major: 4 }).unwrap(),
+    );
+    configs.insert(ValidatorSet::CONFIG_ID, vec![0xff, 0xff]);
+    let payload = OnChainConfigPayload::new(1, Arc::new(configs));
+
+    let report = payload.check_all(&[
+        Version::CONFIG_ID,
+        ValidatorSet::CONFIG_ID,
+        VMPublishingOption::CONFIG_ID,
+    ]);
+    assert!(!report.is_ok());
+    assert_eq!(report.succeeded, vec![Version::CONFIG_ID]);
+    let failed: Vec<_> = report.failed.iter().map(|(id, _)| *id).collect();
+    assert_eq!(
+        failed,
+        vec![ValidatorSet::CONFIG_ID, VMPublishingOption::CONFIG_ID]
+    );
+}
+
+#[test]
+fn test_diff_reports_changed_added_and_removed_configs() {
+    let version = |major| bcs::to_bytes(&Version { major }).unwrap();
+    let mut old_configs = HashMap::new();
+    old_configs.insert(Version::CONFIG_ID, version(3));
+    old_configs.insert(ValidatorSet::CONFIG_ID, vec![0]);
+    old_configs.insert(VMPublishingOption::CONFIG_ID, vec![1]);
+    let old_payload = OnChainConfigPayload::new(1, Arc::new(old_configs.clone()));
+
+    assert!(old_payload.diff(&old_payload).is_empty());
+
+    let mut new_configs = old_configs;
+    new_configs.insert(Version::CONFIG_ID, version(4));
+    new_configs.remove(&ValidatorSet::CONFIG_ID);
+    new_configs.insert(VMConfig::CONFIG_ID, vec![2]);
+    let new_payload = OnChainConfigPayload::new(2, Arc::new(new_configs));
+
+    let mut expected = vec![
+        Version::CONFIG_ID,
+        ValidatorSet::CONFIG_ID,
+        VMConfig::CONFIG_ID,
+    ];
+    expected.sort();
+    assert_eq!(old_payload.diff(&new_payload), expected);
+    assert_eq!(new_payload.diff(&old_payload), expected);
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ExtraTestConfig {
+    value: u64,
+}
+
+impl OnChainConfig for ExtraTestConfig {
+    const IDENTIFIER: &'static str = "ExtraTestConfig";
+}
+
+#[test]
+fn test_register_extra_config() {
+    assert!(register_extra_config(Version::CONFIG_ID).is_err());
+    assert!(!ON_CHAIN_CONFIG_REGISTRY.contains(&GasScheduleV2::CONFIG_ID));
+    assert!(extra_configs().contains(&GasScheduleV2::CONFIG_ID));
+
+    register_extra_config(ExtraTestConfig::CONFIG_ID).unwrap();
+    assert!(extra_configs().contains(&ExtraTestConfig::CONFIG_ID));
+    assert!(register_extra_config(ExtraTestConfig::CONFIG_ID).is_err());
+}
+
+#[test]
+fn test_get_distinguishes_missing_and_malformed_configs() {
+    let mut configs = HashMap::new();
+    configs.insert(ValidatorSet::CONFIG_ID, vec![0xff, 0xff]);
+    let payload = OnChainConfigPayload::new(1, Arc::new(configs));
+
+    assert_eq!(
+        payload.get::<Version>().unwrap_err(),
+        ConfigError::NotInPayload(Version::CONFIG_ID)
+    );
+    assert!(matches!(
+        payload.get::<ValidatorSet>().unwrap_err(),
+        ConfigError::Deserialize(config_id, _) if config_id == ValidatorSet::CONFIG_ID
+    ));
+}
+
+#[test]
+fn test_validate_registry_collects_every_failure() {
+    let mut configs = HashMap::new();
+    configs.insert(
+        Version::CONFIG_ID,
+        bcs::to_bytes(&Version { major: 4 }).unwrap(),
+    );
+    configs.insert(ValidatorSet::CONFIG_ID, vec![0xff, 0xff]);
+    let payload = OnChainConfigPayload::new(1, Arc::new(configs));
+
+    let errors = payload.validate_registry().unwrap_err();
+    assert_eq!(errors.len(), ON_CHAIN_CONFIG_REGISTRY.len() - 1);
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        ConfigError::Deserialize(config_id, _) if *config_id == ValidatorSet::CONFIG_ID
+    )));
+    assert!(errors.contains(&ConfigError::NotInPayload(VMConfig::CONFIG_ID)));
+}